@@ -1,59 +1,464 @@
-use starknet::core::types::SyncStatusType;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Instant, SystemTime};
+
+use ratatui::layout::Rect;
+use serde::Deserialize;
+use starknet::core::types::{BlockId, EmittedEvent, PriceUnit, SyncStatusType};
 use tokio::sync::mpsc as tmpsc;
 
-use crate::radar::Radar;
+use crate::chain::{BlockSummary, ChainEvent, Comparison, L1Message, Reorg, Throughput};
+use crate::collector::SamplerHealth;
+use crate::config::{ChartOptions, Charts, Config};
+use crate::explorer::{Answer, EventSubscription, Query};
+use crate::keymap::{Context, Keymap};
+use crate::radar::{Radar, ResourceUsage, TargetStatus};
+use crate::ui::layout::{neighbour, Breakpoint, FocusDirection, Layouts, Node, Panel};
+use crate::ui::render::page_area;
+use crate::ui::theme::{Theme, Themes};
+
+/// Latest blocks kept in the feed.
+const BLOCK_FEED_LENGTH: usize = 50;
+/// Latest messages from L1 kept in their list.
+const L1_MESSAGE_HISTORY: usize = 100;
+/// Latest events of the subscription kept in the stream.
+const EVENT_STREAM_LENGTH: usize = 200;
+/// Latest throughput samples kept in the charts, one per poll of the node.
+const THROUGHPUT_HISTORY: usize = 100;
 
 pub struct App {
     pub should_quit: bool,
+    pub should_suspend: bool,
+    pub tab: Tab,
+    pub focus: Panel,
+    pub zoomed: bool,
+    pub show_help: bool,
+    pub viewport: Rect,
+    pub layouts: Layouts,
+    pub themes: Themes,
+    pub keymap: Keymap,
+    pub network_unit: RateUnit,
+    pub show_fd_kinds: bool,
+    /// Whether gas prices are shown in ETH or in STRK.
+    pub gas_unit: PriceUnit,
+    pub mouse_capture: bool,
+    /// Last known mouse position, as (column, row).
+    pub pointer: Option<(u16, u16)>,
+    /// How many lines each log panel is scrolled up from its latest line.
+    pub log_scroll: HashMap<Panel, usize>,
+    pub charts: Charts,
+    configured_charts: Charts,
+    /// Sample the time cursor is on, as an index in the series.
+    pub cursor: Option<usize>,
+    /// Command being typed, without its leading `:`.
+    pub prompt: Option<String>,
+    pub command_error: Option<String>,
+    /// Row selected in the zoomed blocks feed, counted from the newest block.
+    pub block_selection: usize,
+    pub inspector: Option<Inspector>,
+    /// Events followed on the events page, `None` until the operator picks a contract.
+    pub event_subscription: Option<EventSubscription>,
+    /// Displayed metrics, frozen while paused.
     pub data: Metrics,
+    /// Metrics collected in the background while paused.
+    live: Option<Box<Metrics>>,
     radar: Radar,
 }
 
+#[derive(Clone)]
 pub struct Metrics {
+    pub target_status: TargetStatus,
+    /// When each sample of the series below was taken.
+    pub timestamps: Vec<SystemTime>,
+    /// Head and sync status of the node from the last poll of the chain watcher, an error until
+    /// the first one answers or times out.
     pub block_number: Result<u64, String>,
     pub syncing: Result<SyncStatusType, String>,
+    /// Last comparison with the reference node, `None` without one.
+    pub reference: Option<Result<Comparison, String>>,
+    /// Latest blocks, oldest first.
+    pub blocks: VecDeque<BlockSummary>,
+    /// Last block accepted on L1, from the block statuses.
+    pub l1_head: Result<Option<u64>, String>,
+    /// When the last block accepted on L1 last moved.
+    pub l1_updated_at: Option<Instant>,
+    /// Latest messages from L1, oldest first.
+    pub l1_messages: VecDeque<L1Message>,
+    /// Latest events of the subscription, oldest first.
+    pub events: VecDeque<EmittedEvent>,
+    /// Why the last events could not be read, cleared once they are.
+    pub events_error: Option<String>,
+    /// Reorgs noticed since the start, oldest first.
+    pub reorgs: Vec<Reorg>,
+    /// Chain progress per second, one sample per poll of the node.
+    pub blocks_rate: Vec<f64>,
+    pub transactions_rate: Vec<f64>,
+    pub events_rate: Vec<f64>,
+    pub steps_rate: Vec<f64>,
     pub cpu_name: String,
     pub cpu_usage: Vec<f64>,
+    pub cpu_sampler: SamplerHealth,
     pub memory_usage: Vec<u64>,
     pub total_memory: u64,
     pub disk_name: String,
     pub disk_size: u64,
     pub disk_usage: u64,
     pub available_storage: u64,
-    pub rx_flow: Vec<f64>, // Mb/s
+    pub resources: Option<ResourceUsage>,
+    pub rx_flow: Vec<f64>, // B/s
     pub tx_flow: Vec<f64>,
+    pub rx_total: u64, // B
+    pub tx_total: u64,
     pub l1_logs: Vec<Option<String>>,
     pub l2_logs: Vec<Option<String>>,
 }
 
+/// What the operator asked the node about, shown over the page until closed.
+pub struct Inspector {
+    pub query: Query,
+    /// `None` until the node answers.
+    pub answer: Option<Result<Answer, String>>,
+    /// Transaction selected in the block.
+    pub selected: usize,
+    /// Whether the selected transaction is opened, with its receipt.
+    pub expanded: bool,
+    /// Lines scrolled in the opened or looked up transaction, or in the messages.
+    pub scroll: u16,
+}
+
+impl Inspector {
+    /// Transactions and messages are shown in a popup over the page, blocks in place of it.
+    pub fn is_popup(&self) -> bool {
+        matches!(self.query, Query::Transaction(_) | Query::Messages(_))
+    }
+    fn transaction_count(&self) -> usize {
+        match &self.answer {
+            Some(Ok(Answer::Block(block))) => block.transactions.len(),
+            _ => 0,
+        }
+    }
+}
+
+/// Pages of the TUI, in tab bar order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tab {
+    Overview,
+    Chain,
+    Logs,
+    System,
+    Storage,
+    Network,
+    Alerts,
+    Events,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 8] =
+        [Tab::Overview, Tab::Chain, Tab::Logs, Tab::System, Tab::Storage, Tab::Network, Tab::Alerts, Tab::Events];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Overview => "Overview",
+            Tab::Chain => "Chain",
+            Tab::Logs => "Logs",
+            Tab::System => "System",
+            Tab::Storage => "Storage",
+            Tab::Network => "Network",
+            Tab::Alerts => "Alerts",
+            Tab::Events => "Events",
+        }
+    }
+    pub fn index(self) -> usize {
+        Tab::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+    pub fn next(self) -> Self {
+        Tab::ALL[(self.index() + 1) % Tab::ALL.len()]
+    }
+    pub fn previous(self) -> Self {
+        Tab::ALL[(self.index() + Tab::ALL.len() - 1) % Tab::ALL.len()]
+    }
+}
+
+/// Unit used to display network rates, cycled at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateUnit {
+    SiBits,
+    IecBits,
+    SiBytes,
+    IecBytes,
+}
+
+impl RateUnit {
+    pub fn next(self) -> Self {
+        match self {
+            RateUnit::SiBits => RateUnit::IecBits,
+            RateUnit::IecBits => RateUnit::SiBytes,
+            RateUnit::SiBytes => RateUnit::IecBytes,
+            RateUnit::IecBytes => RateUnit::SiBits,
+        }
+    }
+    pub fn is_bits(self) -> bool {
+        matches!(self, RateUnit::SiBits | RateUnit::IecBits)
+    }
+    pub fn is_iec(self) -> bool {
+        matches!(self, RateUnit::IecBits | RateUnit::IecBytes)
+    }
+}
+
 impl App {
-    pub fn new(storage_path: &str, logs_rx: tmpsc::Receiver<String>) -> Result<Self, String> {
-        let mut radar = Radar::new(storage_path, logs_rx)?;
+    pub fn new(storage_path: &str, logs_rx: tmpsc::Receiver<String>, config: Config) -> Result<Self, String> {
+        let reference_url = config.reference_rpc_url.as_deref();
+        let mut radar = Radar::new(storage_path, logs_rx, config.target, &config.rpc_url, reference_url)?;
+        let target_status = radar.get_target_status();
         let total_memory = radar.get_total_system_memory();
+        let cpu_name = radar.get_cpu_name().unwrap_or("unknown".to_string());
 
         Ok(Self {
             should_quit: false,
+            should_suspend: false,
+            tab: Tab::Overview,
+            focus: Panel::L2Logs,
+            zoomed: false,
+            show_help: false,
+            viewport: Rect::default(),
+            layouts: Layouts::new(config.layout),
+            themes: Themes::new(config.theme.as_deref()),
+            keymap: Keymap::new(config.keymap.as_deref()),
+            network_unit: RateUnit::SiBits,
+            show_fd_kinds: false,
+            gas_unit: PriceUnit::Wei,
+            mouse_capture: config.mouse,
+            pointer: None,
+            log_scroll: HashMap::new(),
+            charts: config.charts,
+            configured_charts: config.charts,
+            cursor: None,
+            prompt: None,
+            command_error: None,
+            block_selection: 0,
+            inspector: None,
+            event_subscription: None,
+            live: None,
             radar,
             data: Metrics {
-                block_number: Ok(0),
-                syncing: Ok(SyncStatusType::NotSyncing),
-                cpu_name: "unknown".to_string(),
+                target_status,
+                timestamps: vec![SystemTime::now(); 100],
+                block_number: Err("Waiting for the node".to_string()),
+                syncing: Err("Waiting for the node".to_string()),
+                reference: None,
+                blocks: VecDeque::with_capacity(BLOCK_FEED_LENGTH),
+                l1_head: Err("Waiting for the node".to_string()),
+                l1_updated_at: None,
+                l1_messages: VecDeque::with_capacity(L1_MESSAGE_HISTORY),
+                events: VecDeque::with_capacity(EVENT_STREAM_LENGTH),
+                events_error: None,
+                reorgs: Vec::new(),
+                blocks_rate: Vec::with_capacity(THROUGHPUT_HISTORY),
+                transactions_rate: Vec::with_capacity(THROUGHPUT_HISTORY),
+                events_rate: Vec::with_capacity(THROUGHPUT_HISTORY),
+                steps_rate: Vec::with_capacity(THROUGHPUT_HISTORY),
+                cpu_name,
                 cpu_usage: vec![0.; 100],
+                cpu_sampler: SamplerHealth::default(),
                 memory_usage: vec![0; 100],
                 total_memory,
                 disk_name: "unknown".to_string(),
                 disk_size: 0,
                 disk_usage: 0,
                 available_storage: 0,
+                resources: None,
                 rx_flow: vec![0.; 100],
                 tx_flow: vec![0.; 100],
+                rx_total: 0,
+                tx_total: 0,
                 l1_logs: vec![None; 100],
                 l2_logs: vec![None; 100],
             },
         })
     }
+    pub fn theme(&self) -> &Theme {
+        self.themes.current()
+    }
+    /// What the keys currently act on.
+    pub fn context(&self) -> Context {
+        if self.show_help {
+            Context::Help
+        } else if self.inspector.is_some() {
+            Context::Inspector
+        } else if self.zoomed {
+            Context::Zoomed
+        } else {
+            Context::Dashboard
+        }
+    }
+    /// Layout of the current tab at the current terminal size.
+    pub fn page(&self) -> Node {
+        self.layouts.page(self.tab, Breakpoint::of(self.viewport))
+    }
+    /// Follows a terminal resize, keeping the focus on a panel that is still shown.
+    pub fn resize(&mut self, viewport: Rect) {
+        self.viewport = viewport;
+        let areas = self.page().areas(page_area(viewport));
+        if !self.zoomed && !areas.iter().any(|(panel, _)| *panel == self.focus) {
+            if let Some((panel, _)) = areas.first() {
+                self.focus = *panel;
+            }
+        }
+    }
+    pub fn select_tab(&mut self, tab: Tab) {
+        self.tab = tab;
+        self.zoomed = false;
+        if let Some((panel, _)) = self.page().areas(page_area(self.viewport)).first() {
+            self.focus = *panel;
+        }
+    }
+    pub fn move_focus(&mut self, direction: FocusDirection) {
+        let areas = self.page().areas(page_area(self.viewport));
+        if !areas.iter().any(|(panel, _)| *panel == self.focus) {
+            // The layout was reloaded without the focused panel
+            if let Some((panel, _)) = areas.first() {
+                self.focus = *panel;
+            }
+            return;
+        }
+        if let Some(panel) = neighbour(&areas, self.focus, direction) {
+            self.focus = panel;
+        }
+    }
+    /// Panel drawn at the given cell, if any.
+    pub fn panel_at(&self, column: u16, row: u16) -> Option<Panel> {
+        if self.zoomed {
+            return Some(self.focus);
+        }
+        self.page()
+            .areas(page_area(self.viewport))
+            .into_iter()
+            .find(|(_, area)| {
+                (area.left()..area.right()).contains(&column) && (area.top()..area.bottom()).contains(&row)
+            })
+            .map(|(panel, _)| panel)
+    }
+    /// Scrolls a log panel by `lines`, upwards when positive, without going past its oldest line.
+    pub fn scroll_logs(&mut self, panel: Panel, lines: isize) {
+        let logs = match panel {
+            Panel::L1Logs => &self.data.l1_logs,
+            Panel::L2Logs => &self.data.l2_logs,
+            _ => return,
+        };
+        let max = logs.iter().flatten().count().saturating_sub(1);
+        let scroll = self.log_scroll.entry(panel).or_insert(0);
+        *scroll = scroll.saturating_add_signed(lines).min(max);
+    }
+    pub fn is_paused(&self) -> bool {
+        self.live.is_some()
+    }
+    /// Freezes the displayed metrics, or catches up with the ones collected meanwhile.
+    pub fn toggle_pause(&mut self) {
+        match self.live.take() {
+            Some(live) => self.data = *live,
+            None => self.live = Some(Box::new(self.data.clone())),
+        }
+    }
+    /// Changes the rendering options of the focused panel, when it is a chart, given the ones
+    /// it was configured with.
+    pub fn update_focused_chart(&mut self, change: impl FnOnce(&mut ChartOptions, ChartOptions)) {
+        match self.focus {
+            Panel::Cpu => change(&mut self.charts.cpu, self.configured_charts.cpu),
+            Panel::Memory => change(&mut self.charts.memory, self.configured_charts.memory),
+            Panel::Network => change(&mut self.charts.network, self.configured_charts.network),
+            Panel::Throughput => change(&mut self.charts.throughput, self.configured_charts.throughput),
+            Panel::GasPrice => change(&mut self.charts.gas_price, self.configured_charts.gas_price),
+            _ => {}
+        }
+    }
+    pub fn toggle_cursor(&mut self) {
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => Some(self.data.cpu_usage.len() - 1),
+        };
+    }
+    /// Moves the time cursor by `samples`, towards the latest ones when positive.
+    pub fn move_cursor(&mut self, samples: isize) {
+        let last = self.data.cpu_usage.len() - 1;
+        if let Some(cursor) = self.cursor.as_mut() {
+            *cursor = cursor.saturating_add_signed(samples).min(last);
+        }
+    }
+    /// Asks the node about something and shows the answer once it comes.
+    pub fn inspect(&mut self, query: Query) {
+        self.radar.query(query.clone());
+        self.inspector = Some(Inspector { query, answer: None, selected: 0, expanded: false, scroll: 0 });
+    }
+    /// Follows the events of another contract, from the head on.
+    pub fn watch_events(&mut self, subscription: EventSubscription) {
+        self.radar.watch_events(subscription.clone());
+        self.event_subscription = Some(subscription);
+        for data in std::iter::once(&mut self.data).chain(self.live.as_deref_mut()) {
+            data.events.clear();
+            data.events_error = None;
+        }
+        self.select_tab(Tab::Events);
+    }
+    /// Opens the selected row: a transaction of the inspected block, or a block of the feed.
+    pub fn open_selection(&mut self) {
+        if let Some(inspector) = self.inspector.as_mut() {
+            inspector.expanded = inspector.selected < inspector.transaction_count();
+            inspector.scroll = 0;
+            if let Some(Ok(Answer::Block(block))) = &inspector.answer {
+                let transaction = block.transactions.get(inspector.selected);
+                if let Some(transaction) = transaction.filter(|transaction| transaction.receipt.is_none()) {
+                    self.radar.query(Query::Receipt(transaction.hash));
+                }
+            }
+        } else if self.focus == Panel::Blocks {
+            let selection = if self.zoomed { self.block_selection } else { 0 };
+            if let Some(block) = self.data.blocks.iter().rev().nth(selection) {
+                self.inspect(Query::Block(BlockId::Number(block.number)));
+            }
+        }
+    }
+    /// Moves the selection by `rows`, downwards when positive, or scrolls the opened transaction.
+    pub fn move_selection(&mut self, rows: isize) {
+        match self.inspector.as_mut() {
+            Some(inspector) if inspector.expanded || inspector.is_popup() => {
+                inspector.scroll = inspector.scroll.saturating_add_signed(rows as i16);
+            }
+            Some(inspector) => {
+                let last = inspector.transaction_count().saturating_sub(1);
+                inspector.selected = inspector.selected.saturating_add_signed(rows).min(last);
+            }
+            None if self.zoomed && self.focus == Panel::Blocks => {
+                let last = self.data.blocks.len().saturating_sub(1);
+                self.block_selection = self.block_selection.saturating_add_signed(rows).min(last);
+            }
+            None => {}
+        }
+    }
+    /// Keeps a scrolled log panel on the lines being read when a new one arrives.
+    fn keep_log_position(&mut self, panel: Panel) {
+        if !self.is_paused() && self.log_scroll.get(&panel).is_some_and(|scroll| *scroll > 0) {
+            self.scroll_logs(panel, 1);
+        }
+    }
     pub async fn update_metrics(&mut self) {
+        self.layouts.reload_if_changed();
+        // Collection goes on behind the frozen metrics
+        if let Some(live) = self.live.as_mut() {
+            std::mem::swap(&mut self.data, live);
+        }
+        self.collect_metrics();
+        if let Some(live) = self.live.as_mut() {
+            std::mem::swap(&mut self.data, live);
+        } else if let Some(cursor) = self.cursor.as_mut() {
+            // The cursor stays on its sample as the series scroll
+            *cursor = cursor.saturating_sub(1);
+        }
+    }
+    fn collect_metrics(&mut self) {
         self.radar.snapshot();
+        self.data.target_status = self.radar.get_target_status();
+        self.data.timestamps.rotate_left(1);
+        self.data.timestamps[99] = SystemTime::now();
 
         self.data.cpu_usage.rotate_left(1);
         if let Some(cpu_usage) = self.radar.get_cpu_usage() {
@@ -61,28 +466,139 @@ impl App {
         } else {
             self.data.cpu_usage[99] = self.data.cpu_usage[98];
         }
+        self.data.cpu_sampler = self.radar.get_cpu_sampler_health();
 
         self.data.memory_usage.rotate_left(1);
         self.data.memory_usage[99] = self.radar.get_memory_usage().unwrap_or(0);
 
+        self.data.disk_name = self.radar.get_disk_name().unwrap_or("unknown".to_string());
         self.data.disk_size = self.radar.get_total_storage().unwrap_or(0);
         self.data.disk_usage = self.radar.get_storage_usage();
         self.data.available_storage = self.radar.get_available_storage().unwrap_or(0);
 
+        let fd_kinds = self.show_fd_kinds || (self.zoomed && self.focus == Panel::Resources);
+        self.data.resources = self.radar.get_resource_usage(fd_kinds);
+
         self.data.rx_flow.rotate_left(1);
         self.data.tx_flow.rotate_left(1);
-        let (rxf, txf) = self.radar.get_network_usage().unwrap_or((0., 0.));
-        self.data.rx_flow[99] = (rxf * 1000.).round() / 1000.;
-        self.data.tx_flow[99] = (txf * 1000.).round() / 1000.;
+        let (rxf, txf) = self.radar.get_network_usage().unwrap_or((self.data.rx_flow[98], self.data.tx_flow[98]));
+        self.data.rx_flow[99] = rxf;
+        self.data.tx_flow[99] = txf;
+        (self.data.rx_total, self.data.tx_total) = self.radar.get_network_totals();
+
+        for event in self.radar.get_chain_events() {
+            self.follow_chain(event);
+        }
 
         let (l1_log, l2_log) = self.radar.get_logs();
         if l1_log.is_some() {
             self.data.l1_logs.rotate_left(1);
             self.data.l1_logs[99] = l1_log;
+            self.keep_log_position(Panel::L1Logs);
         }
         if l2_log.is_some() {
             self.data.l2_logs.rotate_left(1);
             self.data.l2_logs[99] = l2_log;
+            self.keep_log_position(Panel::L2Logs);
+        }
+    }
+    fn follow_chain(&mut self, event: ChainEvent) {
+        match event {
+            ChainEvent::Head(head) => self.data.block_number = head,
+            ChainEvent::Syncing(syncing) => self.data.syncing = syncing,
+            ChainEvent::Reference(comparison) => self.data.reference = Some(comparison),
+            ChainEvent::Block(block) => {
+                let blocks = &mut self.data.blocks;
+                let new_head = blocks.back().is_none_or(|head| head.number < block.number);
+                // A block seen again replaces the one at its height
+                blocks.retain(|known| known.number != block.number);
+                let position = blocks.partition_point(|known| known.number < block.number);
+                blocks.insert(position, block);
+                while blocks.len() > BLOCK_FEED_LENGTH {
+                    blocks.pop_front();
+                }
+                // The selection stays on its block as new ones come on top
+                if new_head && self.block_selection > 0 && !self.is_paused() {
+                    self.block_selection = (self.block_selection + 1).min(BLOCK_FEED_LENGTH - 1);
+                }
+            }
+            ChainEvent::L1Head(Ok(l1_head)) => {
+                // The first answer tells where L1 is, not when it got there
+                if matches!(self.data.l1_head, Ok(known) if known != l1_head) {
+                    self.data.l1_updated_at = Some(Instant::now());
+                }
+                self.data.l1_head = Ok(l1_head);
+            }
+            ChainEvent::L1Head(Err(err)) => self.data.l1_head = Err(err),
+            ChainEvent::L1Message(message) => {
+                if self.data.l1_messages.len() == L1_MESSAGE_HISTORY {
+                    self.data.l1_messages.pop_front();
+                }
+                self.data.l1_messages.push_back(message);
+            }
+            ChainEvent::Reorg(reorg) => {
+                // Their replacements come next
+                self.data.blocks.retain(|block| block.number < reorg.number);
+                self.data.l1_messages.retain(|message| message.block_number < reorg.number);
+                self.block_selection = self.block_selection.min(self.data.blocks.len().saturating_sub(1));
+                self.data.reorgs.push(reorg);
+            }
+            ChainEvent::Throughput(Throughput { blocks, transactions, events, steps }) => {
+                for (rates, rate) in [
+                    (&mut self.data.blocks_rate, blocks),
+                    (&mut self.data.transactions_rate, transactions),
+                    (&mut self.data.events_rate, events),
+                    (&mut self.data.steps_rate, steps),
+                ] {
+                    if rates.len() == THROUGHPUT_HISTORY {
+                        rates.remove(0);
+                    }
+                    rates.push(rate);
+                }
+            }
+            ChainEvent::Status { number, status } => {
+                if let Some(block) = self.data.blocks.iter_mut().find(|block| block.number == number) {
+                    block.status = status;
+                }
+            }
+            ChainEvent::Events { subscription, events } => {
+                // Events of subscriptions since replaced by another are dropped
+                if self.event_subscription.as_ref() != Some(&subscription) {
+                    return;
+                }
+                match events {
+                    Ok(events) => {
+                        self.data.events_error = None;
+                        for event in events {
+                            if self.data.events.len() == EVENT_STREAM_LENGTH {
+                                self.data.events.pop_front();
+                            }
+                            self.data.events.push_back(event);
+                        }
+                    }
+                    Err(err) => self.data.events_error = Some(err),
+                }
+            }
+            ChainEvent::Answer { query: Query::Receipt(hash), answer } => {
+                // Receipts go to their transaction in the inspected block
+                let receipt = match answer {
+                    Ok(Answer::Receipt(receipt)) => Ok(*receipt),
+                    Ok(_) => return,
+                    Err(err) => Err(err),
+                };
+                if let Some(Inspector { answer: Some(Ok(Answer::Block(block))), .. }) = self.inspector.as_mut() {
+                    let transaction = block.transactions.iter_mut().find(|transaction| transaction.hash == hash);
+                    if let Some(transaction) = transaction {
+                        transaction.receipt = Some(receipt);
+                    }
+                }
+            }
+            ChainEvent::Answer { query, answer } => {
+                // Answers to queries since replaced by another are dropped
+                if let Some(inspector) = self.inspector.as_mut().filter(|inspector| inspector.query == query) {
+                    inspector.answer = Some(answer);
+                }
+            }
         }
     }
 }
@@ -0,0 +1,762 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use starknet::core::types::{
+    BlockHashAndNumber, BlockId, BlockStatus, EmittedEvent, EventFilter, FieldElement, L1HandlerTransaction,
+    MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, ResourcePrice, SyncStatusType, Transaction,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::{Provider, Url};
+use tokio::runtime::Runtime;
+
+use crate::collector::StopSignal;
+use crate::explorer::{answer, fetch_receipt, Answer, EventSubscription, Query};
+
+/// How often the node is asked for its head.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Longest wait, for a query or an answer of the node, between two checks of the stop signal.
+const QUERY_WAIT: Duration = Duration::from_millis(20);
+/// Longest wait for the node to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time after which a poll leaves what it has left to do to the next one.
+const POLL_BUDGET: Duration = Duration::from_secs(3);
+/// Most blocks fetched when the head moved by more, the older ones are skipped.
+const CATCH_UP_LIMIT: u64 = 20;
+/// Blocks still on L2 whose status is followed until they are accepted on L1.
+const TRACKED_BLOCKS: usize = 50;
+/// Most receipts read per poll to measure the events and steps of transactions.
+const SAMPLED_RECEIPTS: usize = 50;
+/// How often the last block accepted on L1 is looked for.
+const L1_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Most events asked for in one request.
+const EVENT_CHUNK_SIZE: u64 = 100;
+/// Most event pages read per poll, the others are read on the next ones.
+const EVENT_PAGES_PER_POLL: usize = 10;
+/// Latest blocks whose hash is remembered to notice when the node replaces them.
+const REMEMBERED_BLOCKS: usize = 100;
+
+/// Header of a block as shown in the feed.
+#[derive(Clone, Debug)]
+pub struct BlockSummary {
+    pub number: u64,
+    pub hash: FieldElement,
+    pub parent_hash: FieldElement,
+    pub timestamp: u64,
+    pub transaction_count: usize,
+    pub sequencer: FieldElement,
+    /// Price of L1 gas in wei and in fri. Blocks of RPC 0.6 have no data gas price.
+    pub l1_gas_price: ResourcePrice,
+    pub status: BlockStatus,
+    /// Seconds since the previous block, when it was fetched too.
+    pub block_time: Option<u64>,
+    /// When the TUI first saw the block.
+    pub received_at: Instant,
+}
+
+/// How fast the chain moved since the previous poll, per second.
+///
+/// Blocks are counted from the head. When the node is faster than the watcher, transactions are
+/// extrapolated from the blocks it fetched, and events and steps from a sample of their receipts.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Throughput {
+    pub blocks: f64,
+    pub transactions: f64,
+    pub events: f64,
+    pub steps: f64,
+}
+
+/// Head of the node against the one of the reference node, with the hashes both give for the
+/// highest block they share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub local_head: u64,
+    pub reference_head: u64,
+    /// Timestamps of both heads.
+    pub local_time: u64,
+    pub reference_time: u64,
+    pub height: u64,
+    pub local_hash: FieldElement,
+    pub reference_hash: FieldElement,
+}
+
+impl Comparison {
+    /// Blocks the node is behind the reference, negative when ahead.
+    pub fn lag_blocks(&self) -> i64 {
+        self.reference_head as i64 - self.local_head as i64
+    }
+    /// Seconds between the timestamps of both heads.
+    pub fn lag_seconds(&self) -> i64 {
+        self.reference_time as i64 - self.local_time as i64
+    }
+    pub fn diverged(&self) -> bool {
+        self.local_hash != self.reference_hash
+    }
+}
+
+/// Message sent from L1, as the L1 handler transaction it led to.
+#[derive(Clone, Debug)]
+pub struct L1Message {
+    pub block_number: u64,
+    pub transaction_hash: FieldElement,
+    /// L1 contract that sent the message, first felt of the calldata.
+    pub from_address: FieldElement,
+    pub contract_address: FieldElement,
+    pub selector: FieldElement,
+    pub nonce: u64,
+    pub payload: Vec<FieldElement>,
+}
+
+impl L1Message {
+    fn new(block_number: u64, transaction: L1HandlerTransaction) -> Self {
+        let mut calldata = transaction.calldata.into_iter();
+        Self {
+            block_number,
+            transaction_hash: transaction.transaction_hash,
+            from_address: calldata.next().unwrap_or(FieldElement::ZERO),
+            contract_address: transaction.contract_address,
+            selector: transaction.entry_point_selector,
+            nonce: transaction.nonce,
+            payload: calldata.collect(),
+        }
+    }
+}
+
+/// Blocks the node replaced or dropped since the watcher saw them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reorg {
+    /// Oldest block replaced.
+    pub number: u64,
+    /// Blocks replaced, at least: the ones older than the remembered hashes are not checked.
+    pub depth: usize,
+    pub old_hash: FieldElement,
+    /// Hash the node now gives for the oldest block replaced, `None` when it has no block there.
+    pub new_hash: Option<FieldElement>,
+    pub detected_at: SystemTime,
+}
+
+/// What the chain watcher learned from the node.
+#[derive(Clone, Debug)]
+pub enum ChainEvent {
+    Head(Result<u64, String>),
+    Syncing(Result<SyncStatusType, String>),
+    Block(BlockSummary),
+    Throughput(Throughput),
+    Reorg(Reorg),
+    L1Message(L1Message),
+    /// Last block accepted on L1, `None` while there is none.
+    L1Head(Result<Option<u64>, String>),
+    Reference(Result<Comparison, String>),
+    /// A block of the feed changed status, usually once accepted on L1.
+    Status { number: u64, status: BlockStatus },
+    Answer { query: Query, answer: Result<Answer, String> },
+    /// Events matching a subscription, in the order the node gave them.
+    Events { subscription: EventSubscription, events: Result<Vec<EmittedEvent>, String> },
+}
+
+/// Starts a thread polling the node for new blocks and the events of the last subscription,
+/// comparing it with the reference node if any, and another one answering `queries` so that they
+/// do not wait for a poll. Each runs its own runtime, so that requests go on whatever the node does
+/// with the one the TUI was started from. Both give up on the request they are waiting for when
+/// stopped, so they end within `QUERY_WAIT`.
+pub fn spawn_chain_watcher(
+    rpc_url: &str,
+    reference_url: Option<&str>,
+    antenna: mpsc::Sender<ChainEvent>,
+    queries: mpsc::Receiver<Query>,
+    subscriptions: mpsc::Receiver<EventSubscription>,
+    stop: StopSignal,
+) -> Result<Vec<JoinHandle<()>>, String> {
+    let parse =
+        |url: &str| Url::parse(url).map_err(|err| format!("Error: {} is not a valid RPC endpoint: {}", url, err));
+    let rpc_url = parse(rpc_url)?;
+    let http = http_client()?;
+    let node = |url: Url| JsonRpcClient::new(HttpTransport::new_with_client(url, http.clone()));
+    let reference = reference_url.map(parse).transpose()?.map(node);
+    let runtime = || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| format!("Error: Could not start the chain watcher: {}", err))
+    };
+    let (poll_runtime, query_runtime) = (runtime()?, runtime()?);
+    let query_client = node(rpc_url.clone());
+    let query_antenna = antenna.clone();
+    let query_stop = stop.clone();
+    let query_url = rpc_url.clone();
+    let query_http = http.clone();
+    let poll_stop = stop.clone();
+    let mut watcher = ChainWatcher {
+        client: node(rpc_url),
+        reference,
+        antenna,
+        stop,
+        poll_deadline: Instant::now(),
+        head: None,
+        previous: None,
+        unconfirmed: VecDeque::new(),
+        seen: VecDeque::new(),
+        subscription: None,
+        l1_head: None,
+        next_l1_poll: Instant::now(),
+        last_poll: None,
+        per_transaction: (0., 0.),
+    };
+    let poller = thread::Builder::new()
+        .name("radar-chain".to_string())
+        .spawn(move || {
+            while !watcher.stop.is_stopped() {
+                // Only the last subscription is followed
+                if let Some(subscription) = subscriptions.try_iter().last() {
+                    watcher.subscription = Some(Subscription { filter: subscription, next_block: None, paging: None });
+                }
+                let next_poll = Instant::now() + POLL_INTERVAL;
+                if run_until_stopped(&poll_runtime, &poll_stop, watcher.poll()) != Some(true) {
+                    break;
+                }
+                if !watcher.stop.sleep(next_poll.saturating_duration_since(Instant::now())) {
+                    break;
+                }
+            }
+        })
+        .map_err(|err| format!("Error: Could not start the chain watcher: {}", err))?;
+    let answerer = thread::Builder::new()
+        .name("radar-queries".to_string())
+        .spawn(move || {
+            while !query_stop.is_stopped() {
+                match queries.recv_timeout(QUERY_WAIT) {
+                    Ok(query) => {
+                        let answer = answer(&query_client, &query_http, &query_url, &query);
+                        let Some(answer) = run_until_stopped(&query_runtime, &query_stop, answer) else {
+                            break;
+                        };
+                        if query_antenna.send(ChainEvent::Answer { query, answer }).is_err() {
+                            break;
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        })
+        .map_err(|err| format!("Error: Could not start the chain watcher: {}", err))?;
+    Ok(vec![poller, answerer])
+}
+
+/// Runs `future` on `runtime` unless stopped first, dropping it along with its pending request.
+fn run_until_stopped<T>(runtime: &Runtime, stop: &StopSignal, future: impl Future<Output = T>) -> Option<T> {
+    runtime.block_on(async {
+        tokio::select! {
+            output = future => Some(output),
+            _ = async {
+                while !stop.is_stopped() {
+                    tokio::time::sleep(QUERY_WAIT).await;
+                }
+            } => None,
+        }
+    })
+}
+
+/// HTTP client for the requests to the nodes, giving up on the ones left unanswered.
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|err| format!("Error: Could not create the HTTP client: {:?}", err))
+}
+
+/// Where the watcher stands in the events of a subscription.
+struct Subscription {
+    filter: EventSubscription,
+    /// First block whose events were not all read, `None` until the head is known.
+    next_block: Option<u64>,
+    /// Last block of the range being read and token of its next page, when it has more.
+    paging: Option<(u64, String)>,
+}
+
+struct ChainWatcher {
+    client: JsonRpcClient<HttpTransport>,
+    reference: Option<JsonRpcClient<HttpTransport>>,
+    antenna: mpsc::Sender<ChainEvent>,
+    stop: StopSignal,
+    /// When the current poll is over its budget.
+    poll_deadline: Instant,
+    head: Option<u64>,
+    /// Number and timestamp of the last block fetched.
+    previous: Option<(u64, u64)>,
+    /// Blocks fetched while on L2, oldest first.
+    unconfirmed: VecDeque<u64>,
+    /// Number and hash of the latest blocks fetched, oldest first.
+    seen: VecDeque<(u64, FieldElement)>,
+    subscription: Option<Subscription>,
+    l1_head: Option<u64>,
+    next_l1_poll: Instant,
+    /// When the head was last read, and what it was.
+    last_poll: Option<(Instant, u64)>,
+    /// Events and steps of the last sampled transactions, on average.
+    per_transaction: (f64, f64),
+}
+
+impl ChainWatcher {
+    /// Fetches what changed since the last poll, returns whether anyone is still listening.
+    async fn poll(&mut self) -> bool {
+        self.poll_deadline = Instant::now() + POLL_BUDGET;
+        let syncing = self.client.syncing().await.map_err(|err| format!("Error: {:?}", err));
+        if self.antenna.send(ChainEvent::Syncing(syncing)).is_err() {
+            return false;
+        }
+        let head = match self.client.block_hash_and_number().await {
+            Ok(head) => head,
+            Err(err) => return self.antenna.send(ChainEvent::Head(Err(format!("Error: {:?}", err)))).is_ok(),
+        };
+        if self.antenna.send(ChainEvent::Head(Ok(head.block_number))).is_err() {
+            return false;
+        }
+        if let Some(reference) = &self.reference {
+            let comparison = self.compare(reference, &head).await;
+            if self.antenna.send(ChainEvent::Reference(comparison)).is_err() {
+                return false;
+            }
+        }
+        let moved_back = self.seen.back().is_some_and(|(number, _)| *number > head.block_number);
+        let rewritten = self.seen.iter().any(|(number, hash)| *number == head.block_number && *hash != head.block_hash);
+        if moved_back || rewritten {
+            return self.rewind(head.block_number).await;
+        }
+        let head = head.block_number;
+
+        let first = match self.head {
+            Some(known) if known >= head => head + 1,
+            Some(known) => (known + 1).max(head.saturating_sub(CATCH_UP_LIMIT - 1)),
+            None => head.saturating_sub(CATCH_UP_LIMIT - 1),
+        };
+        let (mut fetched, mut transactions, mut newest) = (0, 0, vec![]);
+        for number in first..=head {
+            // Caught up with on the next poll
+            if self.out_of_time() {
+                break;
+            }
+            match self.fetch(number).await {
+                Some((block, block_transactions)) => {
+                    let previous = self.seen.back().filter(|(previous, _)| previous + 1 == number);
+                    if previous.is_some_and(|(_, hash)| *hash != block.parent_hash) {
+                        return self.rewind(head).await;
+                    }
+                    self.seen.push_back((number, block.hash));
+                    if self.seen.len() > REMEMBERED_BLOCKS {
+                        self.seen.pop_front();
+                    }
+                    if block.status == BlockStatus::AcceptedOnL2 {
+                        self.unconfirmed.push_back(number);
+                    }
+                    fetched += 1;
+                    transactions += block.transaction_count;
+                    newest = block_transactions.iter().map(|transaction| *transaction.transaction_hash()).collect();
+                    if self.antenna.send(ChainEvent::Block(block)).is_err() {
+                        return false;
+                    }
+                    for transaction in block_transactions {
+                        if let Transaction::L1Handler(transaction) = transaction {
+                            if self.antenna.send(ChainEvent::L1Message(L1Message::new(number, transaction))).is_err() {
+                                return false;
+                            }
+                        }
+                    }
+                }
+                // Tried again on the next poll
+                None => return true,
+            }
+            self.head = Some(number);
+        }
+        if let Some(throughput) = self.measure(head, fetched, transactions, &newest).await {
+            if self.antenna.send(ChainEvent::Throughput(throughput)).is_err() {
+                return false;
+            }
+        }
+        while self.unconfirmed.len() > TRACKED_BLOCKS {
+            self.unconfirmed.pop_front();
+        }
+        if !self.poll_events(head).await {
+            return false;
+        }
+        if self.out_of_time() {
+            return true;
+        }
+        if Instant::now() >= self.next_l1_poll {
+            self.next_l1_poll = Instant::now() + L1_POLL_INTERVAL;
+            let l1_head = self.find_l1_head(head).await;
+            if let Ok(l1_head) = l1_head {
+                self.l1_head = l1_head;
+            }
+            if self.antenna.send(ChainEvent::L1Head(l1_head)).is_err() {
+                return false;
+            }
+        }
+        self.refresh_oldest_status().await
+    }
+
+    /// Whether the poll should leave what it has left to do to the next one.
+    fn out_of_time(&self) -> bool {
+        self.stop.is_stopped() || Instant::now() >= self.poll_deadline
+    }
+
+    /// Reads the events of the subscription from the block after the last one read up to the
+    /// head, a few pages at a time, starting from the head when subscribing.
+    async fn poll_events(&mut self, head: u64) -> bool {
+        let Some(subscription) = self.subscription.as_mut() else {
+            return true;
+        };
+        let next_block = *subscription.next_block.get_or_insert(head);
+        let (last_block, mut token) = match subscription.paging.take() {
+            Some((last_block, token)) => (last_block, Some(token)),
+            None if next_block <= head => (head, None),
+            None => return true,
+        };
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(next_block)),
+            to_block: Some(BlockId::Number(last_block)),
+            address: Some(subscription.filter.address),
+            keys: Some(subscription.filter.keys.clone()).filter(|keys| !keys.is_empty()),
+        };
+        for _ in 0..EVENT_PAGES_PER_POLL {
+            let page = self.client.get_events(filter.clone(), token.clone(), EVENT_CHUNK_SIZE).await;
+            let events = match page {
+                Ok(page) => {
+                    token = page.continuation_token;
+                    Ok(page.events)
+                }
+                Err(err) => Err(format!("Error: {:?}", err)),
+            };
+            let failed = events.is_err();
+            let event = ChainEvent::Events { subscription: subscription.filter.clone(), events };
+            if self.antenna.send(event).is_err() {
+                return false;
+            }
+            if failed {
+                break;
+            }
+            if token.is_none() {
+                subscription.next_block = Some(last_block + 1);
+                return true;
+            }
+            if self.stop.is_stopped() || Instant::now() >= self.poll_deadline {
+                break;
+            }
+        }
+        // Read from where it stopped on the next poll
+        subscription.paging = token.map(|token| (last_block, token));
+        true
+    }
+
+    async fn find_l1_head(&self, head: u64) -> Result<Option<u64>, String> {
+        find_last_accepted(self.l1_head, head, |number| self.is_on_l1(number)).await
+    }
+
+    async fn is_on_l1(&self, number: u64) -> Result<bool, String> {
+        match self.client.get_block_with_tx_hashes(BlockId::Number(number)).await {
+            Ok(MaybePendingBlockWithTxHashes::Block(block)) => Ok(block.status == BlockStatus::AcceptedOnL1),
+            Ok(MaybePendingBlockWithTxHashes::PendingBlock(_)) => Ok(false),
+            Err(err) => Err(format!("Error: {:?}", err)),
+        }
+    }
+
+    /// Forgets the remembered blocks the node replaced, so that their replacements are fetched on
+    /// the next poll.
+    async fn rewind(&mut self, head: u64) -> bool {
+        let client = &self.client;
+        let hash_at = |number| async move { header(client, number).await.map(|(hash, _)| hash) };
+        // Tried again on the next poll
+        let Ok(Some(reorg)) = find_reorg(&self.seen, head, hash_at).await else {
+            return true;
+        };
+        self.seen.truncate(self.seen.len() - reorg.depth);
+        self.head = self.seen.back().map(|(number, _)| *number);
+        self.previous = None;
+        self.unconfirmed.retain(|number| *number < reorg.number);
+        self.antenna.send(ChainEvent::Reorg(reorg)).is_ok()
+    }
+
+    async fn compare(
+        &self,
+        reference: &JsonRpcClient<HttpTransport>,
+        local_head: &BlockHashAndNumber,
+    ) -> Result<Comparison, String> {
+        let reference_head = reference.block_hash_and_number().await.map_err(|err| format!("Error: {:?}", err))?;
+        let (_, local_time) = header(&self.client, local_head.block_number).await?;
+        let (_, reference_time) = header(reference, reference_head.block_number).await?;
+        let height = local_head.block_number.min(reference_head.block_number);
+        let local_hash = match height == local_head.block_number {
+            true => local_head.block_hash,
+            false => header(&self.client, height).await?.0,
+        };
+        let reference_hash = match height == reference_head.block_number {
+            true => reference_head.block_hash,
+            false => header(reference, height).await?.0,
+        };
+        Ok(Comparison {
+            local_head: local_head.block_number,
+            reference_head: reference_head.block_number,
+            local_time,
+            reference_time,
+            height,
+            local_hash,
+            reference_hash,
+        })
+    }
+
+    /// Rates since the previous poll, given the blocks fetched in this one and the transactions
+    /// of the newest of them.
+    async fn measure(
+        &mut self,
+        head: u64,
+        fetched: usize,
+        transactions: usize,
+        newest: &[FieldElement],
+    ) -> Option<Throughput> {
+        let (last_time, last_head) = self.last_poll.replace((Instant::now(), head))?;
+        let elapsed = last_time.elapsed().as_secs_f64();
+        if elapsed <= 0. {
+            return None;
+        }
+        let (mut events, mut steps, mut sampled) = (0, 0, 0);
+        for hash in newest.iter().take(SAMPLED_RECEIPTS) {
+            if self.out_of_time() {
+                break;
+            }
+            if let Ok(receipt) = fetch_receipt(&self.client, *hash).await {
+                events += receipt.events.len();
+                steps += receipt.resources.steps;
+                sampled += 1;
+            }
+        }
+        if sampled > 0 {
+            self.per_transaction = (events as f64 / sampled as f64, steps as f64 / sampled as f64);
+        }
+        let blocks = head.saturating_sub(last_head) as f64;
+        let per_block = if fetched > 0 { transactions as f64 / fetched as f64 } else { 0. };
+        let transactions = blocks * per_block / elapsed;
+        Some(Throughput {
+            blocks: blocks / elapsed,
+            transactions,
+            events: transactions * self.per_transaction.0,
+            steps: transactions * self.per_transaction.1,
+        })
+    }
+
+    async fn fetch(&mut self, number: u64) -> Option<(BlockSummary, Vec<Transaction>)> {
+        let MaybePendingBlockWithTxs::Block(block) = self.client.get_block_with_txs(BlockId::Number(number)).await.ok()?
+        else {
+            return None;
+        };
+        let block_time = match self.previous {
+            Some((previous, timestamp)) if previous + 1 == number => Some(block.timestamp.saturating_sub(timestamp)),
+            _ => None,
+        };
+        self.previous = Some((number, block.timestamp));
+        let summary = BlockSummary {
+            number,
+            hash: block.block_hash,
+            parent_hash: block.parent_hash,
+            timestamp: block.timestamp,
+            transaction_count: block.transactions.len(),
+            sequencer: block.sequencer_address,
+            l1_gas_price: block.l1_gas_price,
+            status: block.status,
+            block_time,
+            received_at: Instant::now(),
+        };
+        Some((summary, block.transactions))
+    }
+
+    /// Blocks are accepted on L1 in order, so only the oldest one still on L2 is checked.
+    async fn refresh_oldest_status(&mut self) -> bool {
+        let Some(number) = self.unconfirmed.front().copied() else {
+            return true;
+        };
+        let Ok(MaybePendingBlockWithTxHashes::Block(block)) =
+            self.client.get_block_with_tx_hashes(BlockId::Number(number)).await
+        else {
+            return true;
+        };
+        if block.status == BlockStatus::AcceptedOnL2 {
+            return true;
+        }
+        self.unconfirmed.pop_front();
+        self.antenna.send(ChainEvent::Status { number, status: block.status }).is_ok()
+    }
+}
+
+/// Blocks are accepted on L1 in order, so the last one up to `head` is found by probing further
+/// and further from the `known` one, then bisecting between the last block accepted and the first
+/// not.
+async fn find_last_accepted<F, Fut>(known: Option<u64>, head: u64, mut is_on_l1: F) -> Result<Option<u64>, String>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<bool, String>>,
+{
+    let mut accepted = known.filter(|known| *known <= head);
+    let mut not_accepted = head + 1;
+    if let Some(known) = accepted {
+        let mut step = 1;
+        while known + step < not_accepted {
+            if is_on_l1(known + step).await? {
+                accepted = Some(known + step);
+                step *= 2;
+            } else {
+                not_accepted = known + step;
+            }
+        }
+    }
+    loop {
+        let first_unknown = accepted.map_or(0, |number| number + 1);
+        if first_unknown >= not_accepted {
+            return Ok(accepted);
+        }
+        let middle = first_unknown + (not_accepted - first_unknown) / 2;
+        if is_on_l1(middle).await? {
+            accepted = Some(middle);
+        } else {
+            not_accepted = middle;
+        }
+    }
+}
+
+/// Walks back from the newest of the `seen` blocks to the last one the node still has, given the
+/// hash it now has at each height up to its `head`.
+async fn find_reorg<F, Fut>(
+    seen: &VecDeque<(u64, FieldElement)>,
+    head: u64,
+    mut hash_at: F,
+) -> Result<Option<Reorg>, String>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<FieldElement, String>>,
+{
+    let mut reorg: Option<Reorg> = None;
+    for &(number, old_hash) in seen.iter().rev() {
+        let new_hash = match number <= head {
+            true => Some(hash_at(number).await?),
+            false => None,
+        };
+        if new_hash == Some(old_hash) {
+            break;
+        }
+        let depth = reorg.as_ref().map_or(0, |reorg| reorg.depth) + 1;
+        reorg = Some(Reorg { number, depth, old_hash, new_hash, detected_at: SystemTime::now() });
+    }
+    Ok(reorg)
+}
+
+/// Hash and timestamp of a block.
+async fn header(client: &JsonRpcClient<HttpTransport>, number: u64) -> Result<(FieldElement, u64), String> {
+    match client.get_block_with_tx_hashes(BlockId::Number(number)).await {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => Ok((block.block_hash, block.timestamp)),
+        Ok(MaybePendingBlockWithTxHashes::PendingBlock(_)) => Err(format!("Block {} is still pending", number)),
+        Err(err) => Err(format!("Error: {:?}", err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::ready;
+
+    use super::*;
+
+    fn chain(hashes: &[u64]) -> VecDeque<(u64, FieldElement)> {
+        hashes.iter().enumerate().map(|(number, hash)| (number as u64, FieldElement::from(*hash))).collect()
+    }
+
+    /// Reorg found in `seen` by a node whose blocks have the `node` hashes.
+    async fn reorg(seen: &[u64], node: &[u64]) -> Option<(u64, usize, Option<FieldElement>)> {
+        let head = node.len() as u64 - 1;
+        let node = chain(node);
+        let hash_at = |number: u64| ready(Ok(node[number as usize].1));
+        let reorg = find_reorg(&chain(seen), head, hash_at).await.unwrap()?;
+        assert_eq!(reorg.old_hash, FieldElement::from(seen[reorg.number as usize]));
+        Some((reorg.number, reorg.depth, reorg.new_hash))
+    }
+
+    /// Last block accepted on L1 found from `known` when it is `l1_head`, and the blocks probed.
+    async fn last_accepted(known: Option<u64>, head: u64, l1_head: Option<u64>) -> (Option<u64>, Vec<u64>) {
+        let mut probed = vec![];
+        let is_on_l1 = |number| {
+            probed.push(number);
+            ready(Ok(l1_head.is_some_and(|l1_head| number <= l1_head)))
+        };
+        let found = find_last_accepted(known, head, is_on_l1).await.unwrap();
+        (found, probed)
+    }
+
+    #[tokio::test]
+    async fn find_last_accepted_bisects_from_scratch() {
+        for l1_head in [None, Some(0), Some(37), Some(99), Some(100)] {
+            let (found, probed) = last_accepted(None, 100, l1_head).await;
+            assert_eq!(found, l1_head);
+            assert!(probed.len() <= 7, "{} probes for {:?}", probed.len(), l1_head);
+        }
+    }
+
+    #[tokio::test]
+    async fn find_last_accepted_probes_from_the_known_block() {
+        let (found, probed) = last_accepted(Some(1000), 1100, Some(1003)).await;
+        assert_eq!(found, Some(1003));
+        assert!(probed.iter().all(|number| *number > 1000));
+        assert!(probed.len() <= 4, "{:?}", probed);
+        assert_eq!(last_accepted(Some(1000), 1100, Some(1000)).await, (Some(1000), vec![1001]));
+        assert_eq!(last_accepted(Some(1000), 1000, Some(1000)).await, (Some(1000), vec![]));
+    }
+
+    #[tokio::test]
+    async fn find_last_accepted_forgets_a_known_block_past_the_head() {
+        // The node went back to before the block it said was accepted
+        assert_eq!(last_accepted(Some(50), 40, Some(40)).await.0, Some(40));
+    }
+
+    #[tokio::test]
+    async fn find_last_accepted_stops_on_errors() {
+        let is_on_l1 = |_| ready(Err("Error: timeout".to_string()));
+        assert_eq!(find_last_accepted(None, 10, is_on_l1).await, Err("Error: timeout".to_string()));
+    }
+
+    #[test]
+    fn run_until_stopped_drops_pending_requests() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let stop = StopSignal::default();
+        assert_eq!(run_until_stopped(&runtime, &stop, ready(1)), Some(1));
+        let stopper = stop.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            stopper.stop();
+        });
+        let started = Instant::now();
+        assert_eq!(run_until_stopped(&runtime, &stop, std::future::pending::<()>()), None);
+        assert!(started.elapsed() < REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn find_reorg_ignores_unchanged_blocks() {
+        assert_eq!(reorg(&[10, 11, 12], &[10, 11, 12]).await, None);
+        assert_eq!(reorg(&[10, 11, 12], &[10, 11, 12, 13]).await, None);
+    }
+
+    #[tokio::test]
+    async fn find_reorg_walks_back_to_the_common_block() {
+        assert_eq!(reorg(&[10, 11, 12], &[10, 11, 22]).await, Some((2, 1, Some(FieldElement::from(22u64)))));
+        assert_eq!(reorg(&[10, 11, 12], &[10, 21, 22, 23]).await, Some((1, 2, Some(FieldElement::from(21u64)))));
+        assert_eq!(reorg(&[10, 11, 12], &[20, 21, 22]).await, Some((0, 3, Some(FieldElement::from(20u64)))));
+    }
+
+    #[tokio::test]
+    async fn find_reorg_counts_blocks_the_node_dropped() {
+        assert_eq!(reorg(&[10, 11, 12, 13], &[10, 11]).await, Some((2, 2, None)));
+        assert_eq!(reorg(&[10, 11, 12, 13], &[10, 21]).await, Some((1, 3, Some(FieldElement::from(21u64)))));
+    }
+
+    #[tokio::test]
+    async fn find_reorg_gives_up_when_the_node_does_not_answer() {
+        let hash_at = |_| ready(Err("Error: timeout".to_string()));
+        assert_eq!(find_reorg(&chain(&[10, 11]), 1, hash_at).await, Err("Error: timeout".to_string()));
+    }
+}
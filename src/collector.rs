@@ -0,0 +1,71 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+/// Granularity at which a sleeping collector notices it has been stopped.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Cancellation flag shared by every background collector spawned by the radar.
+#[derive(Clone, Debug, Default)]
+pub struct StopSignal(Arc<AtomicBool>);
+
+impl StopSignal {
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+    /// Sleeps for `duration` unless stopped in the meantime, returns whether the collector
+    /// should keep running.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        while !self.is_stopped() {
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+            std::thread::sleep(STOP_POLL_INTERVAL.min(deadline - now));
+        }
+        false
+    }
+}
+
+/// Receiving end of a collector, remembering when it last heard from it.
+pub struct Sampler<T> {
+    antenna: mpsc::Receiver<T>,
+    last_sample: Option<Instant>,
+}
+
+impl<T> Sampler<T> {
+    pub fn new(antenna: mpsc::Receiver<T>) -> Self {
+        Self { antenna, last_sample: None }
+    }
+    /// Drains every pending sample and returns the most recent one, if any arrived.
+    pub fn latest(&mut self) -> Option<T> {
+        let latest = self.antenna.try_iter().last();
+        if latest.is_some() {
+            self.last_sample = Some(Instant::now());
+        }
+        latest
+    }
+    /// Time elapsed since the last sample was received, `None` if none ever was.
+    pub fn age(&self) -> Option<Duration> {
+        self.last_sample.map(|at| at.elapsed())
+    }
+}
+
+/// Freshness of a collector, as displayed next to the data it produces.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SamplerHealth {
+    pub last_sample_age: Option<Duration>,
+}
+
+impl SamplerHealth {
+    /// Data is considered stale when no sample arrived for this long.
+    pub const STALE_AFTER: Duration = Duration::from_secs(2);
+
+    pub fn is_stale(&self) -> bool {
+        self.last_sample_age.is_none_or(|age| age > Self::STALE_AFTER)
+    }
+}
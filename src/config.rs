@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use crate::signals::ShutdownHandle;
+
+/// Process (or group of processes) whose resources the TUI reports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// A single process.
+    Pid(u32),
+    /// Every process with this name, aggregated.
+    Name(String),
+    /// A process and all of its descendants, aggregated.
+    Tree(u32),
+}
+
+/// How a chart turns the measured samples into the plotted line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Smoothing {
+    /// Measured samples only, nothing drawn between them.
+    Raw,
+    /// Average over a window of this many samples centered on each one.
+    MovingAverage(usize),
+    /// Exponential moving average, weighting the newest sample by this factor in `0..=1`.
+    Exponential(f64),
+}
+
+/// Symbol used to draw a chart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartMarker {
+    Braille,
+    Dot,
+    Block,
+    Bar,
+}
+
+/// Scale of the y axis of a chart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YScale {
+    Linear,
+    /// Logarithmic, to read small values next to spikes.
+    Log,
+}
+
+/// Range of the y axis of a chart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum YBounds {
+    /// From zero to the highest sample shown.
+    Auto,
+    /// From zero to what the resource can reach: 100% of the CPU, all of the memory. Charts without
+    /// such a limit behave as `Auto`.
+    Capacity,
+    /// Fixed range, in the unit of the chart: percents, bytes or bytes, blocks, transactions,
+    /// events or steps per second.
+    Fixed(f64, f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartOptions {
+    pub smoothing: Smoothing,
+    pub marker: ChartMarker,
+    pub scale: YScale,
+    pub bounds: YBounds,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        Self {
+            smoothing: Smoothing::MovingAverage(5),
+            marker: ChartMarker::Braille,
+            scale: YScale::Linear,
+            bounds: YBounds::Capacity,
+        }
+    }
+}
+
+/// Rendering options of each chart, which can also be changed at runtime.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Charts {
+    pub cpu: ChartOptions,
+    pub memory: ChartOptions,
+    pub network: ChartOptions,
+    pub throughput: ChartOptions,
+    pub gas_price: ChartOptions,
+}
+
+/// Settings provided by the embedding node when starting the TUI.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub target: Target,
+    /// JSON-RPC endpoint of the node, queried for the chain panels.
+    pub rpc_url: String,
+    /// JSON-RPC endpoint of a trusted node the head of ours is compared with.
+    pub reference_rpc_url: Option<String>,
+    /// Page layouts file, reloaded when it changes.
+    pub layout: Option<PathBuf>,
+    /// Theme file overriding the colors of a built-in theme.
+    pub theme: Option<PathBuf>,
+    /// Keymap file overriding the default key bindings.
+    pub keymap: Option<PathBuf>,
+    /// Whether the mouse is captured at startup, it can be released at runtime to select text.
+    pub mouse: bool,
+    pub charts: Charts,
+    /// Keep a clone of this handle to stop the TUI from the node.
+    pub shutdown: ShutdownHandle,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            target: Target::Pid(std::process::id()),
+            rpc_url: "http://localhost:9944".to_string(),
+            reference_rpc_url: None,
+            layout: None,
+            theme: None,
+            keymap: None,
+            mouse: true,
+            charts: Charts::default(),
+            shutdown: ShutdownHandle::new(),
+        }
+    }
+}
@@ -1,45 +1,206 @@
 use std::time::Duration;
 
 use anyhow::{Ok, Result};
-use crossterm::event::Event::Key;
-use crossterm::event::KeyCode::Char;
-use crossterm::event::{self};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::prelude::{CrosstermBackend, Terminal};
+use starknet::core::types::PriceUnit;
 use tokio::sync::mpsc;
 
-use crate::app::App;
+use crate::app::{App, Tab};
+use crate::config::{Config, YBounds};
+use crate::explorer::Command;
+use crate::keymap::Action;
+use crate::signals::{self, SignalListener, TuiSignal};
+use crate::ui::layout::FocusDirection;
 use crate::ui::render;
 
 pub async fn run(storage_path: &str, logs_rx: mpsc::Receiver<String>) -> Result<()> {
+    run_with_config(storage_path, logs_rx, Config::default()).await
+}
+
+pub async fn run_with_config(storage_path: &str, logs_rx: mpsc::Receiver<String>, config: Config) -> Result<()> {
+    let shutdown = config.shutdown.clone();
     let mut t = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
-    let mut app = App::new(storage_path, logs_rx).unwrap();
+    let mut app = App::new(storage_path, logs_rx, config).map_err(anyhow::Error::msg)?;
+    let mut signals = SignalListener::new()?;
+    let mut caught = None;
 
+    render::install_panic_hook();
+    render::set_mouse_capture(app.mouse_capture)?;
     render::startup()?;
     loop {
         update(&mut app).await?;
+        if app.mouse_capture != render::mouse_capture() {
+            render::set_mouse_capture(app.mouse_capture)?;
+        }
+        match signals.try_recv() {
+            Some(TuiSignal::Quit(signal)) => {
+                app.should_quit = true;
+                caught = Some(signal);
+            }
+            Some(TuiSignal::Suspend) => app.should_suspend = true,
+            None => {}
+        }
+        // The terminal is restored behind our back when anything panics
+        if app.should_quit || shutdown.is_shutdown() || !render::is_active() {
+            break;
+        }
+        if app.should_suspend {
+            app.should_suspend = false;
+            render::shutdown()?;
+            signals::suspend_process();
+            render::startup()?;
+            t.clear()?;
+        }
+        app.resize(t.size()?);
         t.draw(|f| {
             render::ui(&app, f);
         })?;
-        if app.should_quit {
-            break;
-        }
     }
     render::shutdown()?;
+    // The node handles the signals again, including the one that stopped the TUI
+    drop(signals);
+    if let Some(signal) = caught {
+        signals::deliver(signal);
+    }
     Ok(())
 }
 
-#[allow(clippy::single_match)]
 async fn update(app: &mut App) -> Result<()> {
     app.update_metrics().await;
     if event::poll(Duration::from_millis(50))? {
-        if let Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Press {
-                match key.code {
-                    Char('q') => app.should_quit = true,
-                    _ => {}
+        match event::read()? {
+            Event::Key(key) if key.kind == event::KeyEventKind::Press && app.prompt.is_some() => {
+                handle_prompt_key(app, key)
+            }
+            Event::Key(key) if key.kind == event::KeyEventKind::Press => {
+                if let Some(action) = app.keymap.action(&key, app.context()) {
+                    handle_action(app, action);
                 }
             }
+            Event::Mouse(mouse) => handle_mouse(app, mouse),
+            _ => {}
         }
     }
     Ok(())
 }
+
+/// Keys typed while the command prompt is open edit the command instead of triggering actions.
+fn handle_prompt_key(app: &mut App, key: KeyEvent) {
+    let Some(prompt) = app.prompt.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => app.prompt = None,
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => app.prompt = None,
+        KeyCode::Enter => {
+            let command = app.prompt.take().unwrap_or_default();
+            match Command::parse(&command) {
+                Result::Ok(Command::Query(query)) => app.inspect(query),
+                Result::Ok(Command::Watch(subscription)) => app.watch_events(subscription),
+                Err(err) => app.command_error = Some(err),
+            }
+        }
+        KeyCode::Backspace if prompt.is_empty() => app.prompt = None,
+        KeyCode::Backspace => {
+            prompt.pop();
+        }
+        KeyCode::Char(c) => prompt.push(c),
+        _ => {}
+    }
+}
+
+/// Lines scrolled by one step of the mouse wheel.
+const SCROLL_STEP: isize = 3;
+
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    app.pointer = Some((mouse.column, mouse.row));
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) if app.show_help => app.show_help = false,
+        MouseEventKind::ScrollUp if app.inspector.is_some() => app.move_selection(-1),
+        MouseEventKind::ScrollDown if app.inspector.is_some() => app.move_selection(1),
+        // The page is hidden behind the inspector
+        _ if app.inspector.is_some() => {}
+        MouseEventKind::Down(MouseButton::Left) if app.zoomed => app.zoomed = false,
+        MouseEventKind::Down(MouseButton::Left) => match app.panel_at(mouse.column, mouse.row) {
+            Some(panel) if panel == app.focus && panel.is_chart() => app.zoomed = true,
+            Some(panel) => app.focus = panel,
+            None => {}
+        },
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            let lines = if mouse.kind == MouseEventKind::ScrollUp { SCROLL_STEP } else { -SCROLL_STEP };
+            if let Some(panel) = app.panel_at(mouse.column, mouse.row).filter(|panel| panel.is_logs()) {
+                app.scroll_logs(panel, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_action(app: &mut App, action: Action) {
+    match action {
+        Action::Quit => app.should_quit = true,
+        Action::Suspend => app.should_suspend = true,
+        Action::Help => app.show_help = !app.show_help,
+        // Closes whatever is on top, and quits once nothing is left
+        Action::Back if app.show_help => app.show_help = false,
+        Action::Back if app.layouts.error.is_some() => app.layouts.error = None,
+        Action::Back if app.themes.error.is_some() => app.themes.error = None,
+        Action::Back if app.keymap.error.is_some() => app.keymap.error = None,
+        Action::Back if app.command_error.is_some() => app.command_error = None,
+        Action::Back if app.inspector.as_ref().is_some_and(|inspector| inspector.expanded) => {
+            if let Some(inspector) = app.inspector.as_mut() {
+                inspector.expanded = false;
+            }
+        }
+        Action::Back if app.inspector.is_some() => app.inspector = None,
+        Action::Back if app.zoomed => app.zoomed = false,
+        Action::Back => app.should_quit = true,
+        Action::NextTab => app.select_tab(app.tab.next()),
+        Action::PreviousTab => app.select_tab(app.tab.previous()),
+        Action::ShowOverview => app.select_tab(Tab::Overview),
+        Action::ShowChain => app.select_tab(Tab::Chain),
+        Action::ShowLogs => app.select_tab(Tab::Logs),
+        Action::ShowSystem => app.select_tab(Tab::System),
+        Action::ShowStorage => app.select_tab(Tab::Storage),
+        Action::ShowNetwork => app.select_tab(Tab::Network),
+        Action::ShowAlerts => app.select_tab(Tab::Alerts),
+        Action::ShowEvents => app.select_tab(Tab::Events),
+        Action::FocusLeft => app.move_focus(FocusDirection::Left),
+        Action::FocusRight => app.move_focus(FocusDirection::Right),
+        Action::FocusUp => app.move_focus(FocusDirection::Up),
+        Action::FocusDown => app.move_focus(FocusDirection::Down),
+        Action::ToggleZoom => app.zoomed = !app.zoomed,
+        Action::CycleNetworkUnit => app.network_unit = app.network_unit.next(),
+        Action::ToggleFdKinds => app.show_fd_kinds = !app.show_fd_kinds,
+        Action::ToggleGasUnit => {
+            app.gas_unit = match app.gas_unit {
+                PriceUnit::Wei => PriceUnit::Fri,
+                PriceUnit::Fri => PriceUnit::Wei,
+            }
+        }
+        Action::CycleTheme => app.themes.cycle(),
+        Action::TogglePause => app.toggle_pause(),
+        Action::ToggleCursor => app.toggle_cursor(),
+        Action::CursorBackward => app.move_cursor(-1),
+        Action::CursorForward => app.move_cursor(1),
+        Action::CycleSmoothing => app.update_focused_chart(|options, _| options.smoothing = options.smoothing.next()),
+        Action::CycleMarker => app.update_focused_chart(|options, _| options.marker = options.marker.next()),
+        Action::ToggleLogScale => app.update_focused_chart(|options, _| options.scale = options.scale.next()),
+        Action::ToggleAutoBounds => app.update_focused_chart(|options, configured| {
+            options.bounds = match (options.bounds, configured.bounds) {
+                (YBounds::Auto, YBounds::Auto) => YBounds::Capacity,
+                (YBounds::Auto, bounds) => bounds,
+                _ => YBounds::Auto,
+            }
+        }),
+        Action::SelectPrevious => app.move_selection(-1),
+        Action::SelectNext => app.move_selection(1),
+        Action::Inspect => app.open_selection(),
+        Action::Command => app.prompt = Some(String::new()),
+        Action::ToggleMouse => {
+            app.mouse_capture = !app.mouse_capture;
+            app.pointer = None;
+        }
+    }
+}
@@ -0,0 +1,470 @@
+use serde::Deserialize;
+use starknet::core::types::{
+    BlockId, BlockStatus, BlockTag, DeclareTransaction, DeployAccountTransaction, Event, ExecutionResources,
+    ExecutionResult, FeePayment, FieldElement, InvokeTransaction, MaybePendingBlockWithTxs,
+    MaybePendingTransactionReceipt, MsgToL1, PendingTransactionReceipt, PriceUnit, ResourceBoundsMapping, Transaction,
+    TransactionFinalityStatus, TransactionReceipt, TransactionStatus,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::{Provider, Url};
+
+/// Commands suggested after the block lookup when the typed one is not understood.
+const OTHER_COMMANDS: &str = "`tx <hash>`, `msg <L1 transaction hash>` or `events <address> [key ...]`";
+/// JSON-RPC error code of a method the node does not have.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// A command typed by the operator in the prompt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Query(Query),
+    Watch(EventSubscription),
+}
+
+impl Command {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        match words.next() {
+            Some("events") => EventSubscription::parse(words).map(Command::Watch),
+            _ => Query::parse(input).map(Command::Query),
+        }
+    }
+}
+
+/// Something the operator asked the node about, answered by the chain watcher.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Query {
+    Block(BlockId),
+    Transaction(FieldElement),
+    /// Messages sent from L1 by the Ethereum transaction of this hash, which may not fit in a felt.
+    Messages(String),
+    /// Receipt of a transaction opened in the inspected block.
+    Receipt(FieldElement),
+}
+
+impl Query {
+    /// Reads a command typed by the operator: `block <number, hash or latest>`, or just the block,
+    /// `tx <hash>` and `msg <L1 transaction hash>`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("tx"), Some(hash), None) => FieldElement::from_hex_be(hash)
+                .map(Query::Transaction)
+                .map_err(|_| format!("{} is not a valid transaction hash", hash)),
+            (Some("msg"), Some(hash), None) => parse_l1_hash(hash).map(Query::Messages),
+            (Some("block"), Some(block), None) | (Some(block), None, None) => parse_block_id(block).map(Query::Block),
+            (None, _, _) => Err(format!("Type a block number or hash, {}", OTHER_COMMANDS)),
+            _ => Err(format!("Unknown command {:?}, try `block <number or hash>`, {}", input.trim(), OTHER_COMMANDS)),
+        }
+    }
+}
+
+/// Events the operator wants to follow: those of a contract, filtered by their keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventSubscription {
+    pub address: FieldElement,
+    /// Accepted values of each key in order, any value when empty.
+    pub keys: Vec<Vec<FieldElement>>,
+}
+
+impl EventSubscription {
+    /// Reads the arguments of `events <address> [key ...]`, where each key lists its accepted values
+    /// separated by `|`, or is `_` to accept any.
+    fn parse<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<Self, String> {
+        let address = match words.next() {
+            Some(address) => {
+                FieldElement::from_hex_be(address).map_err(|_| format!("{} is not a valid contract address", address))?
+            }
+            None => return Err("Type the address of the contract, then the keys if any".to_string()),
+        };
+        let keys = words
+            .map(|key| match key {
+                "_" => Ok(vec![]),
+                values => values
+                    .split('|')
+                    .map(|value| FieldElement::from_hex_be(value).map_err(|_| format!("{} is not a valid key", value)))
+                    .collect(),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { address, keys })
+    }
+}
+
+fn parse_block_id(raw: &str) -> Result<BlockId, String> {
+    if raw == "latest" {
+        return Ok(BlockId::Tag(BlockTag::Latest));
+    }
+    if raw.starts_with("0x") {
+        return FieldElement::from_hex_be(raw)
+            .map(BlockId::Hash)
+            .map_err(|_| format!("{} is not a valid block hash", raw));
+    }
+    raw.parse().map(BlockId::Number).map_err(|_| format!("{} is neither a block number nor a hash", raw))
+}
+
+fn parse_l1_hash(raw: &str) -> Result<String, String> {
+    match raw.strip_prefix("0x") {
+        Some(digits) if !digits.is_empty() && digits.len() <= 64 && digits.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Ok(raw.to_lowercase())
+        }
+        _ => Err(format!("{} is not a valid L1 transaction hash", raw)),
+    }
+}
+
+/// What the node answered to a query.
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Block(Box<BlockDetail>),
+    Transaction { transaction: Box<TransactionDetail>, status: Result<TransactionStatus, String> },
+    Messages(Vec<MessageStatus>),
+    Receipt(Box<ReceiptDetail>),
+}
+
+/// Where the L1 handler transaction a message from L1 led to stands, as given by
+/// `starknet_getMessagesStatus`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct MessageStatus {
+    pub transaction_hash: String,
+    pub finality_status: String,
+    #[serde(default)]
+    pub failure_reason: Option<String>,
+}
+
+/// A block with everything the inspector shows about it.
+#[derive(Clone, Debug)]
+pub struct BlockDetail {
+    pub number: u64,
+    pub hash: FieldElement,
+    pub parent_hash: FieldElement,
+    pub new_root: FieldElement,
+    pub timestamp: u64,
+    pub sequencer: FieldElement,
+    pub l1_gas_price: FieldElement,
+    pub starknet_version: String,
+    pub status: BlockStatus,
+    pub transactions: Vec<TransactionDetail>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionKind {
+    Invoke,
+    Declare,
+    Deploy,
+    DeployAccount,
+    L1Handler,
+}
+
+impl TransactionKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            TransactionKind::Invoke => "INVOKE",
+            TransactionKind::Declare => "DECLARE",
+            TransactionKind::Deploy => "DEPLOY",
+            TransactionKind::DeployAccount => "DEPLOY_ACCOUNT",
+            TransactionKind::L1Handler => "L1_HANDLER",
+        }
+    }
+}
+
+/// An amount of ETH (in wei) or STRK (in fri).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fee {
+    pub amount: u128,
+    pub unit: PriceUnit,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionDetail {
+    pub hash: FieldElement,
+    pub kind: TransactionKind,
+    /// Account sending the transaction, or contract called by a legacy invoke or an L1 handler.
+    pub sender: Option<FieldElement>,
+    pub nonce: Option<FieldElement>,
+    /// Most the sender agreed to pay, bounded by the L1 gas resources from v3 on.
+    pub max_fee: Option<Fee>,
+    pub calldata: Vec<FieldElement>,
+    /// `None` until fetched, which for the transactions of a block waits until one is opened.
+    pub receipt: Option<Result<ReceiptDetail, String>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReceiptDetail {
+    /// Block the transaction landed in, `None` while it is in the pending block.
+    pub block_number: Option<u64>,
+    /// `None` while the transaction is in the pending block.
+    pub finality: Option<TransactionFinalityStatus>,
+    pub execution: ExecutionResult,
+    pub fee: Fee,
+    pub events: Vec<Event>,
+    pub messages_sent: Vec<MsgToL1>,
+    pub resources: ExecutionResources,
+}
+
+/// Answers a query, with an error message when the node could not. `http` is the client behind
+/// `client`, for the methods it does not have.
+pub async fn answer(
+    client: &JsonRpcClient<HttpTransport>,
+    http: &reqwest::Client,
+    rpc_url: &Url,
+    query: &Query,
+) -> Result<Answer, String> {
+    match query {
+        Query::Block(id) => fetch_block(client, *id).await.map(|block| Answer::Block(Box::new(block))),
+        Query::Transaction(hash) => fetch_transaction(client, *hash).await,
+        Query::Messages(hash) => fetch_messages_status(http, rpc_url, hash).await.map(Answer::Messages),
+        Query::Receipt(hash) => fetch_receipt(client, *hash).await.map(|receipt| Answer::Receipt(Box::new(receipt))),
+    }
+}
+
+/// The provider only speaks RPC 0.6 which has no such method, so it is called directly. Nodes
+/// have it from RPC 0.7 on.
+async fn fetch_messages_status(
+    http: &reqwest::Client,
+    rpc_url: &Url,
+    l1_transaction_hash: &str,
+) -> Result<Vec<MessageStatus>, String> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "starknet_getMessagesStatus",
+        "params": { "transaction_hash": l1_transaction_hash },
+    });
+    let response: serde_json::Value = http
+        .post(rpc_url.clone())
+        .json(&request)
+        .send()
+        .await
+        .map_err(|err| format!("Error: {:?}", err))?
+        .json()
+        .await
+        .map_err(|err| format!("Error: {:?}", err))?;
+    if let Some(error) = response.get("error") {
+        return Err(match error["code"].as_i64() {
+            Some(METHOD_NOT_FOUND) => "The node does not support starknet_getMessagesStatus (RPC 0.7)".to_string(),
+            _ => format!("Error: {}", error["message"]),
+        });
+    }
+    serde_json::from_value(response["result"].clone()).map_err(|err| format!("Error: {:?}", err))
+}
+
+async fn fetch_transaction(client: &JsonRpcClient<HttpTransport>, hash: FieldElement) -> Result<Answer, String> {
+    let transaction = client.get_transaction_by_hash(hash).await.map_err(|err| format!("Error: {:?}", err))?;
+    let receipt = fetch_receipt(client, hash).await;
+    let status = client.get_transaction_status(hash).await.map_err(|err| format!("Error: {:?}", err));
+    Ok(Answer::Transaction { transaction: Box::new(transaction_detail(transaction, Some(receipt))), status })
+}
+
+async fn fetch_block(client: &JsonRpcClient<HttpTransport>, id: BlockId) -> Result<BlockDetail, String> {
+    let block = match client.get_block_with_txs(id).await.map_err(|err| format!("Error: {:?}", err))? {
+        MaybePendingBlockWithTxs::Block(block) => block,
+        MaybePendingBlockWithTxs::PendingBlock(_) => return Err("The pending block has no number yet".to_string()),
+    };
+    // Receipts are only fetched for the transactions opened, a block can hold hundreds of them
+    let transactions = block.transactions.into_iter().map(|transaction| transaction_detail(transaction, None));
+    Ok(BlockDetail {
+        number: block.block_number,
+        hash: block.block_hash,
+        parent_hash: block.parent_hash,
+        new_root: block.new_root,
+        timestamp: block.timestamp,
+        sequencer: block.sequencer_address,
+        l1_gas_price: block.l1_gas_price.price_in_wei,
+        starknet_version: block.starknet_version,
+        status: block.status,
+        transactions: transactions.collect(),
+    })
+}
+
+/// Receipt of one transaction, which may still be in the pending block.
+pub async fn fetch_receipt(client: &JsonRpcClient<HttpTransport>, hash: FieldElement) -> Result<ReceiptDetail, String> {
+    client.get_transaction_receipt(hash).await.map(receipt_detail).map_err(|err| format!("Error: {:?}", err))
+}
+
+fn transaction_detail(transaction: Transaction, receipt: Option<Result<ReceiptDetail, String>>) -> TransactionDetail {
+    use TransactionKind::*;
+    let hash = *transaction.transaction_hash();
+    let (kind, sender, nonce, max_fee, calldata) = match transaction {
+        Transaction::Invoke(InvokeTransaction::V0(tx)) => {
+            (Invoke, Some(tx.contract_address), None, Some(legacy_fee(tx.max_fee)), tx.calldata)
+        }
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => {
+            (Invoke, Some(tx.sender_address), Some(tx.nonce), Some(legacy_fee(tx.max_fee)), tx.calldata)
+        }
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => {
+            (Invoke, Some(tx.sender_address), Some(tx.nonce), Some(bounded_fee(&tx.resource_bounds)), tx.calldata)
+        }
+        Transaction::L1Handler(tx) => {
+            (L1Handler, Some(tx.contract_address), Some(FieldElement::from(tx.nonce)), None, tx.calldata)
+        }
+        Transaction::Declare(DeclareTransaction::V0(tx)) => {
+            (Declare, Some(tx.sender_address), None, Some(legacy_fee(tx.max_fee)), vec![])
+        }
+        Transaction::Declare(DeclareTransaction::V1(tx)) => {
+            (Declare, Some(tx.sender_address), Some(tx.nonce), Some(legacy_fee(tx.max_fee)), vec![])
+        }
+        Transaction::Declare(DeclareTransaction::V2(tx)) => {
+            (Declare, Some(tx.sender_address), Some(tx.nonce), Some(legacy_fee(tx.max_fee)), vec![])
+        }
+        Transaction::Declare(DeclareTransaction::V3(tx)) => {
+            (Declare, Some(tx.sender_address), Some(tx.nonce), Some(bounded_fee(&tx.resource_bounds)), vec![])
+        }
+        Transaction::Deploy(tx) => (Deploy, None, None, None, tx.constructor_calldata),
+        Transaction::DeployAccount(DeployAccountTransaction::V1(tx)) => {
+            (DeployAccount, None, Some(tx.nonce), Some(legacy_fee(tx.max_fee)), tx.constructor_calldata)
+        }
+        Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => {
+            (DeployAccount, None, Some(tx.nonce), Some(bounded_fee(&tx.resource_bounds)), tx.constructor_calldata)
+        }
+    };
+    TransactionDetail { hash, kind, sender, nonce, max_fee, calldata, receipt }
+}
+
+/// Fee of a transaction before v3, paid in ETH.
+fn legacy_fee(max_fee: FieldElement) -> Fee {
+    Fee { amount: u128::try_from(max_fee).unwrap_or(u128::MAX), unit: PriceUnit::Wei }
+}
+
+/// Fee of a v3 transaction, paid in STRK for at most its L1 gas bounds.
+fn bounded_fee(bounds: &ResourceBoundsMapping) -> Fee {
+    let amount = (bounds.l1_gas.max_amount as u128).saturating_mul(bounds.l1_gas.max_price_per_unit);
+    Fee { amount, unit: PriceUnit::Fri }
+}
+
+fn paid_fee(fee: FeePayment) -> Fee {
+    Fee { amount: u128::try_from(fee.amount).unwrap_or(u128::MAX), unit: fee.unit }
+}
+
+fn receipt_detail(receipt: MaybePendingTransactionReceipt) -> ReceiptDetail {
+    macro_rules! detail {
+        ($receipt:ident, $block_number:expr, $finality:expr) => {
+            ReceiptDetail {
+                block_number: $block_number,
+                finality: $finality,
+                execution: $receipt.execution_result,
+                fee: paid_fee($receipt.actual_fee),
+                events: $receipt.events,
+                messages_sent: $receipt.messages_sent,
+                resources: $receipt.execution_resources,
+            }
+        };
+        ($receipt:ident) => {
+            detail!($receipt, Some($receipt.block_number), Some($receipt.finality_status))
+        };
+        (pending $receipt:ident) => {
+            detail!($receipt, None, None)
+        };
+    }
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(receipt) => match receipt {
+            TransactionReceipt::Invoke(receipt) => detail!(receipt),
+            TransactionReceipt::L1Handler(receipt) => detail!(receipt),
+            TransactionReceipt::Declare(receipt) => detail!(receipt),
+            TransactionReceipt::Deploy(receipt) => detail!(receipt),
+            TransactionReceipt::DeployAccount(receipt) => detail!(receipt),
+        },
+        MaybePendingTransactionReceipt::PendingReceipt(receipt) => match receipt {
+            PendingTransactionReceipt::Invoke(receipt) => detail!(pending receipt),
+            PendingTransactionReceipt::L1Handler(receipt) => detail!(pending receipt),
+            PendingTransactionReceipt::Declare(receipt) => detail!(pending receipt),
+            PendingTransactionReceipt::DeployAccount(receipt) => detail!(pending receipt),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::ResourceBounds;
+
+    use super::*;
+
+    fn felt(hex: &str) -> FieldElement {
+        FieldElement::from_hex_be(hex).unwrap()
+    }
+
+    #[test]
+    fn query_reads_blocks() {
+        assert_eq!(Query::parse("12345"), Ok(Query::Block(BlockId::Number(12345))));
+        assert_eq!(Query::parse(" block 7 "), Ok(Query::Block(BlockId::Number(7))));
+        assert_eq!(Query::parse("latest"), Ok(Query::Block(BlockId::Tag(BlockTag::Latest))));
+        assert_eq!(Query::parse("block 0x2a"), Ok(Query::Block(BlockId::Hash(felt("0x2a")))));
+    }
+
+    #[test]
+    fn query_reads_transactions() {
+        assert_eq!(Query::parse("tx 0x1f"), Ok(Query::Transaction(felt("0x1f"))));
+        assert!(Query::parse("tx nope").unwrap_err().contains("not a valid transaction hash"));
+    }
+
+    #[test]
+    fn query_rejects_anything_else() {
+        assert!(Query::parse("").unwrap_err().starts_with("Type a block number or hash"));
+        assert!(Query::parse("-1").unwrap_err().contains("neither a block number nor a hash"));
+        assert!(Query::parse("block 0xzz").unwrap_err().contains("not a valid block hash"));
+        assert!(Query::parse("tx 0x1 0x2").unwrap_err().starts_with("Unknown command"));
+        assert!(Query::parse("receipt 0x1").unwrap_err().starts_with("Unknown command"));
+    }
+
+    #[test]
+    fn query_reads_l1_transaction_hashes() {
+        let hash = format!("0x{}", "AB".repeat(32));
+        assert_eq!(Query::parse(&format!("msg {}", hash)), Ok(Query::Messages(hash.to_lowercase())));
+        assert_eq!(Query::parse("msg 0x1"), Ok(Query::Messages("0x1".to_string())));
+        for invalid in ["msg 0x", "msg 1234", "msg 0xg1", &format!("msg 0x{}", "0".repeat(65))] {
+            assert!(Query::parse(invalid).unwrap_err().contains("not a valid L1 transaction hash"), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn message_status_reads_the_node_answer() {
+        let statuses: Vec<MessageStatus> = serde_json::from_str(
+            r#"[
+                {"transaction_hash": "0x1", "finality_status": "ACCEPTED_ON_L2"},
+                {"transaction_hash": "0x2", "finality_status": "REJECTED", "failure_reason": "nonce"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(statuses[0].failure_reason, None);
+        assert_eq!(statuses[1].finality_status, "REJECTED");
+        assert_eq!(statuses[1].failure_reason.as_deref(), Some("nonce"));
+    }
+
+    #[test]
+    fn command_reads_event_subscriptions() {
+        let subscription = |keys| Command::Watch(EventSubscription { address: felt("0x49d"), keys });
+        assert_eq!(Command::parse("events 0x49d"), Ok(subscription(vec![])));
+        assert_eq!(
+            Command::parse("events 0x49d 0x99|0x9a _ 0x1"),
+            Ok(subscription(vec![vec![felt("0x99"), felt("0x9a")], vec![], vec![felt("0x1")]]))
+        );
+    }
+
+    #[test]
+    fn command_rejects_invalid_subscriptions() {
+        assert!(Command::parse("events").unwrap_err().starts_with("Type the address of the contract"));
+        assert!(Command::parse("events nope").unwrap_err().contains("not a valid contract address"));
+        assert!(Command::parse("events 0x49d 0x1|x").unwrap_err().contains("x is not a valid key"));
+    }
+
+    #[test]
+    fn command_falls_back_to_queries() {
+        assert_eq!(Command::parse("tx 0x1"), Ok(Command::Query(Query::Transaction(felt("0x1")))));
+        assert_eq!(Command::parse("42"), Ok(Command::Query(Query::Block(BlockId::Number(42)))));
+        assert!(Command::parse("watch 0x1").unwrap_err().contains("`events <address> [key ...]`"));
+    }
+
+    #[test]
+    fn legacy_fee_is_the_max_fee_in_wei() {
+        assert_eq!(legacy_fee(felt("0x3e8")), Fee { amount: 1000, unit: PriceUnit::Wei });
+        let huge = felt("0x100000000000000000000000000000000");
+        assert_eq!(legacy_fee(huge), Fee { amount: u128::MAX, unit: PriceUnit::Wei });
+    }
+
+    #[test]
+    fn bounded_fee_is_the_l1_gas_bound_in_fri() {
+        let bounds = |max_amount, max_price_per_unit| ResourceBoundsMapping {
+            l1_gas: ResourceBounds { max_amount, max_price_per_unit },
+            l2_gas: ResourceBounds { max_amount: 0, max_price_per_unit: 0 },
+        };
+        assert_eq!(bounded_fee(&bounds(2_000, 30)), Fee { amount: 60_000, unit: PriceUnit::Fri });
+        assert_eq!(bounded_fee(&bounds(u64::MAX, u128::MAX)), Fee { amount: u128::MAX, unit: PriceUnit::Fri });
+    }
+}
@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Everything a key can trigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Back,
+    Suspend,
+    Help,
+    NextTab,
+    PreviousTab,
+    ShowOverview,
+    ShowChain,
+    ShowLogs,
+    ShowSystem,
+    ShowStorage,
+    ShowNetwork,
+    ShowAlerts,
+    ShowEvents,
+    FocusLeft,
+    FocusRight,
+    FocusUp,
+    FocusDown,
+    ToggleZoom,
+    CycleNetworkUnit,
+    ToggleFdKinds,
+    ToggleGasUnit,
+    CycleTheme,
+    ToggleMouse,
+    TogglePause,
+    ToggleCursor,
+    CursorBackward,
+    CursorForward,
+    CycleSmoothing,
+    CycleMarker,
+    ToggleLogScale,
+    ToggleAutoBounds,
+    SelectPrevious,
+    SelectNext,
+    Inspect,
+    Command,
+}
+
+/// Situations in which different sets of actions apply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Context {
+    Dashboard,
+    Zoomed,
+    Inspector,
+    Help,
+}
+
+impl Context {
+    pub const ALL: [Context; 4] = [Context::Dashboard, Context::Zoomed, Context::Inspector, Context::Help];
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Back => "Close the current view, quit from the dashboard",
+            Action::Suspend => "Suspend to the shell",
+            Action::Help => "Toggle this help",
+            Action::NextTab => "Next tab",
+            Action::PreviousTab => "Previous tab",
+            Action::ShowOverview => "Overview tab",
+            Action::ShowChain => "Chain tab",
+            Action::ShowLogs => "Logs tab",
+            Action::ShowSystem => "System tab",
+            Action::ShowStorage => "Storage tab",
+            Action::ShowNetwork => "Network tab",
+            Action::ShowAlerts => "Alerts tab",
+            Action::ShowEvents => "Events tab",
+            Action::FocusLeft => "Focus the panel on the left",
+            Action::FocusRight => "Focus the panel on the right",
+            Action::FocusUp => "Focus the panel above",
+            Action::FocusDown => "Focus the panel below",
+            Action::ToggleZoom => "Zoom in or out of the focused panel",
+            Action::CycleNetworkUnit => "Change the network rate unit",
+            Action::ToggleFdKinds => "Show file descriptor types",
+            Action::ToggleGasUnit => "Show gas prices in ETH or STRK",
+            Action::CycleTheme => "Change the color theme",
+            Action::ToggleMouse => "Capture the mouse or release it to select text",
+            Action::TogglePause => "Freeze the display, collection goes on",
+            Action::ToggleCursor => "Show the time cursor on the charts",
+            Action::CursorBackward => "Move the time cursor to an older sample",
+            Action::CursorForward => "Move the time cursor to a newer sample",
+            Action::CycleSmoothing => "Change the smoothing of the focused chart",
+            Action::CycleMarker => "Change the symbols of the focused chart",
+            Action::ToggleLogScale => "Switch the focused chart to a log scale",
+            Action::ToggleAutoBounds => "Fit the focused chart to its values",
+            Action::SelectPrevious => "Select the previous row",
+            Action::SelectNext => "Select the next row",
+            Action::Inspect => "Open the selected block or transaction",
+            Action::Command => "Look up a block, a transaction or messages from L1",
+        }
+    }
+    pub fn applies_to(self, context: Context) -> bool {
+        match context {
+            Context::Help => matches!(self, Action::Quit | Action::Back | Action::Suspend | Action::Help),
+            Context::Inspector => matches!(
+                self,
+                Action::Quit
+                    | Action::Back
+                    | Action::Suspend
+                    | Action::Help
+                    | Action::SelectPrevious
+                    | Action::SelectNext
+                    | Action::Inspect
+                    | Action::Command
+                    | Action::CycleTheme
+                    | Action::ToggleMouse
+            ),
+            Context::Zoomed => {
+                !matches!(self, Action::FocusLeft | Action::FocusRight | Action::FocusUp | Action::FocusDown)
+            }
+            // Rows are only selected in zoomed panels, where the arrows do not move the focus
+            Context::Dashboard => !matches!(self, Action::SelectPrevious | Action::SelectNext),
+        }
+    }
+    /// Whether both actions apply somewhere, so that they cannot share a key.
+    fn overlaps(self, other: Action) -> bool {
+        Context::ALL.iter().any(|context| self.applies_to(*context) && other.applies_to(*context))
+    }
+}
+
+/// A key with its modifiers, written like `q`, `ctrl-c`, `shift-tab` or `f1` in keymap files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of the character (or of BackTab) and terminals disagree on reporting it
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid key {:?}", raw);
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = raw;
+        // A lone "-" is a key, not a separator
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+            key = rest;
+        }
+        let code = match key.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => match (lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()), key.chars().next()) {
+                (Some(n), _) => KeyCode::F(n),
+                (None, Some(c)) if key.chars().count() == 1 => KeyCode::Char(c),
+                _ => return Err(invalid()),
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Keymap file: the chords bound to each overridden action, for instance:
+///
+/// ```toml
+/// [keys]
+/// quit = ["q", "ctrl-c"]
+/// cycle_theme = ["T"]
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeymapFile {
+    #[serde(default)]
+    keys: HashMap<Action, Vec<String>>,
+}
+
+/// Maps key chords to actions, in the order they are listed in the help.
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyChord>)>,
+    pub error: Option<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bind = |action, keys: &[&str]| {
+            (action, keys.iter().map(|key| KeyChord::parse(key).expect("valid default key")).collect())
+        };
+        Self {
+            bindings: vec![
+                bind(Action::Quit, &["q", "ctrl-c"]),
+                bind(Action::Back, &["esc"]),
+                bind(Action::Suspend, &["ctrl-z"]),
+                bind(Action::Help, &["?"]),
+                bind(Action::NextTab, &["tab"]),
+                bind(Action::PreviousTab, &["shift-tab"]),
+                bind(Action::ShowOverview, &["1"]),
+                bind(Action::ShowChain, &["2"]),
+                bind(Action::ShowLogs, &["3"]),
+                bind(Action::ShowSystem, &["4"]),
+                bind(Action::ShowStorage, &["5"]),
+                bind(Action::ShowNetwork, &["6"]),
+                bind(Action::ShowAlerts, &["7"]),
+                bind(Action::ShowEvents, &["8"]),
+                bind(Action::FocusLeft, &["left", "h"]),
+                bind(Action::FocusRight, &["right", "l"]),
+                bind(Action::FocusUp, &["up", "k"]),
+                bind(Action::FocusDown, &["down", "j"]),
+                bind(Action::ToggleZoom, &["enter"]),
+                bind(Action::CycleNetworkUnit, &["u"]),
+                bind(Action::ToggleFdKinds, &["f"]),
+                bind(Action::ToggleGasUnit, &["g"]),
+                bind(Action::CycleTheme, &["t"]),
+                bind(Action::ToggleMouse, &["m"]),
+                bind(Action::TogglePause, &["p", "space"]),
+                bind(Action::ToggleCursor, &["c"]),
+                bind(Action::CursorBackward, &["["]),
+                bind(Action::CursorForward, &["]"]),
+                bind(Action::CycleSmoothing, &["s"]),
+                bind(Action::CycleMarker, &["x"]),
+                bind(Action::ToggleLogScale, &["y"]),
+                bind(Action::ToggleAutoBounds, &["b"]),
+                bind(Action::SelectPrevious, &["up", "k"]),
+                bind(Action::SelectNext, &["down", "j"]),
+                bind(Action::Inspect, &["i"]),
+                bind(Action::Command, &[":"]),
+            ],
+            error: None,
+        }
+    }
+}
+
+impl Keymap {
+    /// Default keymap with the bindings of `path` applied over it.
+    pub fn new(path: Option<&Path>) -> Self {
+        let mut keymap = Self::default();
+        if let Some(path) = path {
+            if let Err(err) = keymap.apply_file(path) {
+                keymap = Self { error: Some(format!("{}: {}", path.display(), err)), ..Self::default() };
+            }
+        }
+        keymap
+    }
+    fn apply_file(&mut self, path: &Path) -> Result<(), String> {
+        let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        self.apply(&content)
+    }
+    fn apply(&mut self, content: &str) -> Result<(), String> {
+        let file: KeymapFile = toml::from_str(content).map_err(|err| err.to_string())?;
+        for (action, keys) in file.keys {
+            let chords = keys.iter().map(|key| KeyChord::parse(key)).collect::<Result<Vec<_>, _>>()?;
+            // A key can trigger different actions in different contexts
+            for (_, bound) in self.bindings.iter_mut().filter(|(bound, _)| bound.overlaps(action)) {
+                bound.retain(|chord| !chords.contains(chord));
+            }
+            if let Some((_, bound)) = self.bindings.iter_mut().find(|(bound, _)| *bound == action) {
+                *bound = chords;
+            }
+        }
+        Ok(())
+    }
+    /// Action bound to the key, if it makes sense in `context`.
+    pub fn action(&self, key: &KeyEvent, context: Context) -> Option<Action> {
+        let chord = KeyChord::new(key.code, key.modifiers);
+        self.bindings
+            .iter()
+            .find(|(action, chords)| action.applies_to(context) && chords.contains(&chord))
+            .map(|(action, _)| *action)
+    }
+    /// Bindings to list in the help for `context`.
+    pub fn help(&self, context: Context) -> Vec<(String, &'static str)> {
+        self.bindings
+            .iter()
+            .filter(|(action, chords)| action.applies_to(context) && !chords.is_empty())
+            .map(|(action, chords)| {
+                let keys: Vec<String> = chords.iter().map(KeyChord::to_string).collect();
+                (keys.join(", "), action.description())
+            })
+            .collect()
+    }
+    /// First key bound to `action`, to mention it in hints.
+    pub fn key_for(&self, action: Action) -> Option<KeyChord> {
+        self.bindings.iter().find(|(bound, _)| *bound == action).and_then(|(_, chords)| chords.first().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord { code, modifiers }
+    }
+
+    fn press(keymap: &Keymap, raw: &str, context: Context) -> Option<Action> {
+        let chord = KeyChord::parse(raw).unwrap();
+        keymap.action(&KeyEvent::new(chord.code, chord.modifiers), context)
+    }
+
+    #[test]
+    fn key_chord_parses_keys_and_modifiers() {
+        assert_eq!(KeyChord::parse("q"), Ok(chord(KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("Q"), Ok(chord(KeyCode::Char('Q'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("ctrl-c"), Ok(chord(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert_eq!(
+            KeyChord::parse("Ctrl-Alt-PageUp"),
+            Ok(chord(KeyCode::PageUp, KeyModifiers::CONTROL | KeyModifiers::ALT))
+        );
+        assert_eq!(KeyChord::parse("space"), Ok(chord(KeyCode::Char(' '), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("f12"), Ok(chord(KeyCode::F(12), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("shift-left"), Ok(chord(KeyCode::Left, KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn key_chord_drops_shift_from_characters_and_backtab() {
+        assert_eq!(KeyChord::parse("shift-tab"), Ok(chord(KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("backtab"), KeyChord::parse("shift-tab"));
+        assert_eq!(KeyChord::parse("shift-a"), Ok(chord(KeyCode::Char('a'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn key_chord_takes_a_lone_dash_as_a_key() {
+        assert_eq!(KeyChord::parse("-"), Ok(chord(KeyCode::Char('-'), KeyModifiers::NONE)));
+        assert_eq!(KeyChord::parse("ctrl--"), Ok(chord(KeyCode::Char('-'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn key_chord_rejects_unknown_keys() {
+        for raw in ["", "ctrl-", "hyper-x", "ab", "ctrl-enterr"] {
+            assert!(KeyChord::parse(raw).is_err(), "{:?} was accepted", raw);
+        }
+    }
+
+    #[test]
+    fn key_chord_displays_as_in_the_help() {
+        assert_eq!(KeyChord::parse("ctrl-c").unwrap().to_string(), "Ctrl-c");
+        assert_eq!(KeyChord::parse("shift-tab").unwrap().to_string(), "Shift-Tab");
+        assert_eq!(KeyChord::parse("space").unwrap().to_string(), "Space");
+    }
+
+    #[test]
+    fn overrides_replace_the_default_keys() {
+        let mut keymap = Keymap::default();
+        keymap.apply("[keys]\nquit = [\"x\"]").unwrap();
+        assert_eq!(press(&keymap, "x", Context::Dashboard), Some(Action::Quit));
+        assert_eq!(press(&keymap, "q", Context::Dashboard), None);
+        assert_eq!(press(&keymap, "ctrl-c", Context::Dashboard), None);
+        assert_eq!(keymap.key_for(Action::Quit), KeyChord::parse("x").ok());
+    }
+
+    #[test]
+    fn overrides_take_the_key_from_overlapping_actions() {
+        let mut keymap = Keymap::default();
+        keymap.apply("[keys]\ncycle_theme = [\"q\"]").unwrap();
+        assert_eq!(press(&keymap, "q", Context::Dashboard), Some(Action::CycleTheme));
+        assert_eq!(press(&keymap, "ctrl-c", Context::Dashboard), Some(Action::Quit));
+        // The arrows focus panels on the dashboard and select rows in the inspector
+        keymap.apply("[keys]\nselect_next = [\"n\"]").unwrap();
+        assert_eq!(press(&keymap, "down", Context::Dashboard), Some(Action::FocusDown));
+        assert_eq!(press(&keymap, "n", Context::Inspector), Some(Action::SelectNext));
+    }
+
+    #[test]
+    fn invalid_keymap_files_keep_the_defaults() {
+        assert!(Keymap::default().apply("[keys]\nlaunch = [\"l\"]").is_err());
+        assert!(Keymap::default().apply("[keys]\nquit = [\"hyper-q\"]").is_err());
+        let keymap = Keymap::new(Some(Path::new("/nonexistent/keymap.toml")));
+        assert!(keymap.error.is_some());
+        assert_eq!(press(&keymap, "q", Context::Dashboard), Some(Action::Quit));
+    }
+}
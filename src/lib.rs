@@ -1,6 +1,14 @@
 mod app;
+mod chain;
+mod collector;
+mod config;
 mod deoxys_ui;
+mod explorer;
+mod keymap;
 mod radar;
+mod signals;
 mod ui;
 
+pub use config::*;
 pub use deoxys_ui::*;
+pub use signals::ShutdownHandle;
@@ -1,91 +1,265 @@
+use std::fs;
 use std::path::Path;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
 use std::thread;
-use std::time::{self, Duration, SystemTime};
+use std::time::{Duration, Instant};
 
-use starknet::core::types::SyncStatusType;
-use starknet::providers::jsonrpc::{self, HttpTransport};
-use starknet::providers::{Provider, Url};
-use sysinfo::{Disks, Networks, Pid, ProcessRefreshKind, System};
+use sysinfo::{CpuRefreshKind, Disks, Networks, Pid, Process, ProcessRefreshKind, RefreshKind, System};
+
+use crate::chain::{spawn_chain_watcher, ChainEvent};
+use crate::collector::{Sampler, SamplerHealth, StopSignal};
+use crate::config::Target;
+use crate::explorer::{EventSubscription, Query};
+
+/// How long a restart stays reported after reattaching to the new process.
+const RESTART_NOTICE: Duration = Duration::from_secs(10);
+/// How long resource usage is reused before procfs is read again.
+const RESOURCE_REFRESH: Duration = Duration::from_secs(1);
+/// Longest wait for the collectors to stop when the radar is dropped, the late ones are detached.
+const COLLECTORS_SHUTDOWN: Duration = Duration::from_millis(500);
+
+/// Whether the monitored process is currently reachable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TargetStatus {
+    Running { pid: u32, processes: usize },
+    Restarted { pid: u32, processes: usize },
+    Exited,
+}
+
+/// The process the radar is currently attached to, identified by pid and start time so that a
+/// recycled pid is not mistaken for the original process.
+struct Attachment {
+    pid: Pid,
+    start_time: u64,
+    name: String,
+}
+
+/// Process resource usage measured against the limits enforced by the kernel.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceUsage {
+    pub open_fds: u64,
+    pub fd_soft_limit: Option<u64>, // None when unlimited
+    pub fd_hard_limit: Option<u64>,
+    pub threads: u64,
+    pub mmaps: u64,
+    pub max_map_count: Option<u64>,
+    pub fd_kinds: Option<FdKinds>,
+}
+
+/// Open file descriptors grouped by what they point to.
+#[derive(Clone, Debug, Default)]
+pub struct FdKinds {
+    pub files: u64,
+    pub sockets: u64,
+    pub pipes: u64,
+    pub eventfds: u64,
+    pub others: u64,
+}
 
 pub struct Radar {
-    _rpc_client: jsonrpc::JsonRpcClient<HttpTransport>,
     network: Networks,
     system: System,
     disks: Disks,
     storage_directory: String,
-    pid: u32,
-    last_network_refresh_time: SystemTime,
-    cpu_antenna: mpsc::Receiver<f64>,
+    target: Target,
+    attachment: Option<Attachment>,
+    restarted_at: Option<Instant>,
+    /// Pids of the target and its children, shared with the CPU sampler. The list is only ever
+    /// replaced whole, so it is still read after a panic poisoned the lock.
+    target_pids: Arc<Mutex<Vec<Pid>>>,
+    last_network_refresh_time: Instant,
+    /// Last resource usage read and when, as reading it walks the whole of procfs for the target.
+    last_resources: Option<(Instant, Option<ResourceUsage>)>,
+    total_received: u64,
+    total_sent: u64,
+    cpu_sampler: Sampler<f64>,
     logs_antenna: tokio::sync::mpsc::Receiver<String>,
+    chain_antenna: mpsc::Receiver<ChainEvent>,
+    queries: mpsc::Sender<Query>,
+    subscriptions: mpsc::Sender<EventSubscription>,
+    stop: StopSignal,
+    collectors: Vec<thread::JoinHandle<()>>,
 }
 
 impl Radar {
-    pub fn new(target_storage_directory: &str, logs_rx: tokio::sync::mpsc::Receiver<String>) -> Result<Self, String> {
-        let url = Url::parse("localhost:9944").map_err(|_| "Error: Not a Valid URL for RPC endpoint")?;
-        let rpc_provider = jsonrpc::JsonRpcClient::new(HttpTransport::new(url));
+    pub fn new(
+        target_storage_directory: &str,
+        logs_rx: tokio::sync::mpsc::Receiver<String>,
+        target: Target,
+        rpc_url: &str,
+        reference_url: Option<&str>,
+    ) -> Result<Self, String> {
         let sys = System::new_all();
         let disks = Disks::new();
         let mut network: Networks = Networks::new_with_refreshed_list();
         network.refresh();
         let (cpu_tx, cpu_rx) = mpsc::channel::<f64>();
+        let target_pids = Arc::new(Mutex::new(Vec::new()));
+
+        let stop = StopSignal::default();
+
+        let sampled_pids = Arc::clone(&target_pids);
+        let cpu_stop = stop.clone();
+        let cpu_collector = thread::Builder::new()
+            .name("radar-cpu".to_string())
+            .spawn(move || {
+                let mut system = System::new_with_specifics(RefreshKind::new().with_cpu(CpuRefreshKind::new()));
+                let cpus_number = system.cpus().len().max(1);
 
-        thread::spawn(move || {
-            let tx = cpu_tx.clone();
-            let mut system = System::new_all();
-            let cpus_number = system.cpus().len();
-            let pid = std::process::id();
-
-            loop {
-                system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
-                std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-                system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
-                let process = system.process(Pid::from_u32(pid)).unwrap();
-                let usage = process.cpu_usage() as f64 / cpus_number as f64;
-                if tx.send(usage).is_err() {
-                    break;
+                while !cpu_stop.is_stopped() {
+                    system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
+                    if !cpu_stop.sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL) {
+                        break;
+                    }
+                    system.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
+                    let pids = sampled_pids.lock().unwrap_or_else(PoisonError::into_inner).clone();
+                    let usage: f64 = pids
+                        .iter()
+                        .filter_map(|pid| system.process(*pid))
+                        .map(|process| process.cpu_usage() as f64)
+                        .sum();
+                    if cpu_tx.send(usage / cpus_number as f64).is_err() {
+                        break;
+                    }
                 }
-            }
-        });
+            })
+            .map_err(|err| format!("Error: Could not start the CPU collector: {}", err))?;
 
-        Ok(Self {
+        let (chain_tx, chain_rx) = mpsc::channel::<ChainEvent>();
+        let (query_tx, query_rx) = mpsc::channel::<Query>();
+        let (subscription_tx, subscription_rx) = mpsc::channel::<EventSubscription>();
+        let mut collectors = vec![cpu_collector];
+        let watchers = spawn_chain_watcher(rpc_url, reference_url, chain_tx, query_rx, subscription_rx, stop.clone())?;
+        collectors.extend(watchers);
+
+        let mut radar = Self {
             logs_antenna: logs_rx,
-            cpu_antenna: cpu_rx,
-            _rpc_client: rpc_provider,
+            cpu_sampler: Sampler::new(cpu_rx),
+            stop,
+            chain_antenna: chain_rx,
+            queries: query_tx,
+            subscriptions: subscription_tx,
+            collectors,
             disks,
             storage_directory: target_storage_directory.to_string(),
             system: sys,
-            pid: std::process::id(),
+            target,
+            attachment: None,
+            restarted_at: None,
+            target_pids,
             network,
-            last_network_refresh_time: time::SystemTime::now(),
-        })
-    }
-    pub async fn _get_block_number(&self) -> Result<u64, String> {
-        self._rpc_client.block_number().await.map_err(|err| format!("Error: {:?}", err))
-    }
-    pub async fn _get_syncing(&self) -> Result<SyncStatusType, String> {
-        self._rpc_client.syncing().await.map_err(|err| format!("Error: {:?}", err))
+            last_network_refresh_time: Instant::now(),
+            last_resources: None,
+            total_received: 0,
+            total_sent: 0,
+        };
+        radar.locate_target();
+        Ok(radar)
     }
     pub fn snapshot(&mut self) {
-        self.network.refresh();
-        self.last_network_refresh_time = time::SystemTime::now();
         self.system.refresh_processes_specifics(ProcessRefreshKind::new().with_memory());
         self.disks.refresh_list();
+        self.locate_target();
     }
-    pub fn get_cpu_usage(&mut self) -> Option<f64> {
-        match self.cpu_antenna.recv_timeout(Duration::from_millis(10)) {
-            Ok(usage) => Some(usage),
-            _ => None,
+    fn locate_target(&mut self) {
+        let mut alive = self.attachment.as_ref().is_some_and(|attachment| {
+            self.system.process(attachment.pid).is_some_and(|process| process.start_time() == attachment.start_time)
+        });
+        if !alive {
+            let candidate = match (&self.target, &self.attachment) {
+                (Target::Name(name), _) => self.find_by_name(name),
+                // A restarted process gets a new pid, so follow it by name
+                (_, Some(attachment)) => self.find_by_name(&attachment.name),
+                (Target::Pid(pid) | Target::Tree(pid), None) => self.system.process(Pid::from_u32(*pid)),
+            }
+            .map(|process| Attachment {
+                pid: process.pid(),
+                start_time: process.start_time(),
+                name: process.name().to_string(),
+            });
+            if let Some(candidate) = candidate {
+                if self.attachment.is_some() {
+                    self.restarted_at = Some(Instant::now());
+                }
+                self.attachment = Some(candidate);
+                alive = true;
+            }
         }
+
+        let pids = match &self.attachment {
+            Some(attachment) if alive => match &self.target {
+                Target::Pid(_) => vec![attachment.pid],
+                Target::Name(name) => self
+                    .system
+                    .processes()
+                    .values()
+                    .filter(|process| process.thread_kind().is_none() && process.name() == name)
+                    .map(|process| process.pid())
+                    .collect(),
+                Target::Tree(_) => self.process_tree(attachment.pid),
+            },
+            _ => Vec::new(),
+        };
+        *self.target_pids.lock().unwrap_or_else(PoisonError::into_inner) = pids;
+    }
+    fn find_by_name(&self, name: &str) -> Option<&Process> {
+        // The oldest matching process is the one that spawned the others
+        self.system
+            .processes()
+            .values()
+            .filter(|process| process.thread_kind().is_none() && process.name() == name)
+            .min_by_key(|process| process.start_time())
+    }
+    fn process_tree(&self, root: Pid) -> Vec<Pid> {
+        let mut tree = vec![root];
+        let mut i = 0;
+        while i < tree.len() {
+            let parent = tree[i];
+            tree.extend(
+                self.system
+                    .processes()
+                    .values()
+                    .filter(|process| process.thread_kind().is_none() && process.parent() == Some(parent))
+                    .map(|process| process.pid()),
+            );
+            i += 1;
+        }
+        tree
+    }
+    pub fn get_target_status(&self) -> TargetStatus {
+        let processes = self.target_pids.lock().unwrap_or_else(PoisonError::into_inner).len();
+        let Some(attachment) = self.attachment.as_ref().filter(|_| processes > 0) else {
+            return TargetStatus::Exited;
+        };
+        let pid = attachment.pid.as_u32();
+        match self.restarted_at {
+            Some(at) if at.elapsed() < RESTART_NOTICE => TargetStatus::Restarted { pid, processes },
+            _ => TargetStatus::Running { pid, processes },
+        }
+    }
+    pub fn get_cpu_usage(&mut self) -> Option<f64> {
+        self.cpu_sampler.latest()
+    }
+    pub fn get_cpu_sampler_health(&self) -> SamplerHealth {
+        SamplerHealth { last_sample_age: self.cpu_sampler.age() }
     }
     pub fn get_memory_usage(&mut self) -> Option<u64> {
-        let process = self.system.process(Pid::from_u32(self.pid)).unwrap();
-        Some(process.memory())
+        let pids = self.target_pids.lock().unwrap_or_else(PoisonError::into_inner);
+        if pids.is_empty() {
+            return None;
+        }
+        Some(pids.iter().filter_map(|pid| self.system.process(*pid)).map(|process| process.memory()).sum())
     }
     pub fn get_total_system_memory(&mut self) -> u64 {
         self.system.refresh_memory();
         self.system.total_memory()
     }
+    pub fn get_cpu_name(&self) -> Option<String> {
+        self.system.cpus().first().map(|cpu| cpu.brand().trim().to_string()).filter(|brand| !brand.is_empty())
+    }
+    pub fn get_disk_name(&self) -> Option<String> {
+        self.disks.list().first().map(|disk| disk.name().to_string_lossy().into_owned())
+    }
     pub fn get_total_storage(&mut self) -> Option<u64> {
         self.disks.list().first().map(|disk| disk.total_space())
     }
@@ -97,12 +271,87 @@ impl Radar {
         self.disks.list().first().map(|elm| elm.available_space())
     }
     pub fn get_network_usage(&mut self) -> Option<(f64, f64)> {
-        // Returns the data (rx, tx) rate in Mb/s
+        // Returns the data (rx, tx) rate in bytes per second since the previous call
         self.network.refresh();
-        let dt = self.last_network_refresh_time.elapsed().unwrap().as_millis() as f64 / 1000.;
+        let dt = self.last_network_refresh_time.elapsed().as_secs_f64();
+        self.last_network_refresh_time = Instant::now();
         let received: u64 = self.network.into_iter().map(|(_, elm)| elm.received()).sum();
         let sent: u64 = self.network.into_iter().map(|(_, elm)| elm.transmitted()).sum();
-        Some(((received as f64 / dt) / 1000000., (sent as f64 / dt) / 1000000.))
+        self.total_received += received;
+        self.total_sent += sent;
+        if dt <= 0. {
+            return None;
+        }
+        Some((received as f64 / dt, sent as f64 / dt))
+    }
+    pub fn get_network_totals(&self) -> (u64, u64) {
+        // Returns the (rx, tx) bytes exchanged since the radar was started
+        (self.total_received, self.total_sent)
+    }
+    pub fn get_resource_usage(&mut self, with_fd_kinds: bool) -> Option<ResourceUsage> {
+        if let Some((read_at, usage)) = &self.last_resources {
+            let has_fd_kinds = usage.as_ref().map(|usage| usage.fd_kinds.is_some()) != Some(false);
+            if read_at.elapsed() < RESOURCE_REFRESH && (has_fd_kinds || !with_fd_kinds) {
+                return usage.clone();
+            }
+        }
+        let usage = self.read_resource_usage(with_fd_kinds);
+        self.last_resources = Some((Instant::now(), usage.clone()));
+        usage
+    }
+    fn read_resource_usage(&self, with_fd_kinds: bool) -> Option<ResourceUsage> {
+        // Relies on procfs, so only available on Linux. Limits are those of the root process.
+        let pids = self.target_pids.lock().unwrap_or_else(PoisonError::into_inner);
+        let root_dir = format!("/proc/{}", pids.first()?);
+        let (fd_soft_limit, fd_hard_limit) = read_fd_limits(&root_dir).unwrap_or((None, None));
+        let max_map_count =
+            fs::read_to_string("/proc/sys/vm/max_map_count").ok().and_then(|count| count.trim().parse().ok());
+        let mut usage = ResourceUsage {
+            fd_soft_limit,
+            fd_hard_limit,
+            max_map_count,
+            fd_kinds: if with_fd_kinds { Some(FdKinds::default()) } else { None },
+            ..Default::default()
+        };
+        for pid in pids.iter() {
+            let proc_dir = format!("/proc/{}", pid);
+            let Ok(fds) = fs::read_dir(format!("{}/fd", proc_dir)) else {
+                continue;
+            };
+            usage.open_fds += fds.count() as u64;
+            // Listing our own fds opens one more
+            if pid.as_u32() == std::process::id() {
+                usage.open_fds = usage.open_fds.saturating_sub(1);
+            }
+            usage.threads += fs::read_to_string(format!("{}/status", proc_dir))
+                .ok()
+                .and_then(|status| {
+                    status.lines().find_map(|line| line.strip_prefix("Threads:")).and_then(|n| n.trim().parse().ok())
+                })
+                .unwrap_or(0);
+            usage.mmaps +=
+                fs::read_to_string(format!("{}/maps", proc_dir)).map(|maps| maps.lines().count() as u64).unwrap_or(0);
+            if let Some(kinds) = usage.fd_kinds.as_mut() {
+                count_fd_kinds(&proc_dir, kinds);
+            }
+        }
+        if usage.open_fds == 0 && usage.threads == 0 {
+            return None;
+        }
+        Some(usage)
+    }
+    /// Everything the chain watcher reported since the last call, in order.
+    pub fn get_chain_events(&mut self) -> Vec<ChainEvent> {
+        self.chain_antenna.try_iter().collect()
+    }
+    /// Asks the node about something, the answer comes back with the chain events.
+    pub fn query(&self, query: Query) {
+        let _ = self.queries.send(query);
+    }
+    /// Follows the events of a contract instead of the ones followed so far, they come back with
+    /// the chain events.
+    pub fn watch_events(&self, subscription: EventSubscription) {
+        let _ = self.subscriptions.send(subscription);
     }
     pub fn get_logs(&mut self) -> (Option<String>, Option<String>) {
         if let Ok(raw) = self.logs_antenna.try_recv() {
@@ -112,3 +361,53 @@ impl Radar {
         }
     }
 }
+
+impl Drop for Radar {
+    fn drop(&mut self) {
+        self.stop.stop();
+        // Dropped from the node's runtime, which a collector stuck in a system call must not hold
+        let deadline = Instant::now() + COLLECTORS_SHUTDOWN;
+        while self.collectors.iter().any(|collector| !collector.is_finished()) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        for collector in self.collectors.drain(..).filter(|collector| collector.is_finished()) {
+            let _ = collector.join();
+        }
+    }
+}
+
+fn read_fd_limits(proc_dir: &str) -> Option<(Option<u64>, Option<u64>)> {
+    // Line format: "Max open files            1024                 524288               files"
+    let limits = fs::read_to_string(format!("{}/limits", proc_dir)).ok()?;
+    let line = limits.lines().find(|line| line.starts_with("Max open files"))?;
+    let mut values = line.trim_start_matches("Max open files").split_whitespace().map(|value| value.parse().ok());
+    Some((values.next()?, values.next()?))
+}
+
+fn count_fd_kinds(proc_dir: &str, kinds: &mut FdKinds) {
+    let fd_dir = format!("{}/fd", proc_dir);
+    let Ok(fds) = fs::read_dir(&fd_dir) else {
+        return;
+    };
+    for entry in fds.flatten() {
+        let Ok(target) = fs::read_link(entry.path()) else {
+            continue;
+        };
+        // The listing itself, when counting our own fds
+        if target == Path::new(&fd_dir) {
+            continue;
+        }
+        let target = target.to_string_lossy();
+        if target.starts_with("socket:") {
+            kinds.sockets += 1;
+        } else if target.starts_with("pipe:") {
+            kinds.pipes += 1;
+        } else if target == "anon_inode:[eventfd]" {
+            kinds.eventfds += 1;
+        } else if target.starts_with('/') {
+            kinds.files += 1;
+        } else {
+            kinds.others += 1;
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+/// Lets the embedding node stop the TUI gracefully, restoring the terminal on the way out.
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownHandle(Arc<AtomicBool>);
+
+impl ShutdownHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn shutdown(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+    pub fn is_shutdown(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// What the TUI should do in response to a process signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TuiSignal {
+    /// Quit, then deliver the signal again once the terminal is restored.
+    Quit(i32),
+    Suspend,
+}
+
+/// Last signal caught while a listener is installed, 0 when none.
+static CAUGHT: AtomicI32 = AtomicI32::new(0);
+
+#[cfg(unix)]
+extern "C" fn catch(signal: libc::c_int) {
+    CAUGHT.store(signal, Ordering::SeqCst);
+}
+
+/// Catches SIGINT, SIGTERM and SIGTSTP for the UI loop until dropped, then gives them back to
+/// whatever handled them before, so that the node gets them again once the TUI is gone.
+///
+/// While SIGTSTP is caught here its default behaviour is gone, which is why the UI loop stops the
+/// process itself through [`suspend_process`].
+pub struct SignalListener {
+    #[cfg(unix)]
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+}
+
+impl SignalListener {
+    #[cfg(unix)]
+    pub fn new() -> Result<Self> {
+        let mut listener = Self { previous: Vec::new() };
+        for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGTSTP] {
+            // SAFETY: both actions are plain old data, and the handler only stores to an atomic,
+            // which is async-signal-safe.
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = catch as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                action.sa_flags = libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                let mut previous: libc::sigaction = std::mem::zeroed();
+                if libc::sigaction(signal, &action, &mut previous) != 0 {
+                    // Dropping the listener restores the signals caught so far
+                    return Err(std::io::Error::last_os_error().into());
+                }
+                listener.previous.push((signal, previous));
+            }
+        }
+        Ok(listener)
+    }
+
+    #[cfg(not(unix))]
+    pub fn new() -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn try_recv(&mut self) -> Option<TuiSignal> {
+        match CAUGHT.swap(0, Ordering::SeqCst) {
+            0 => None,
+            #[cfg(unix)]
+            libc::SIGTSTP => Some(TuiSignal::Suspend),
+            signal => Some(TuiSignal::Quit(signal)),
+        }
+    }
+}
+
+impl Drop for SignalListener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        for (signal, previous) in self.previous.drain(..) {
+            // SAFETY: `previous` is the action returned by sigaction for this very signal.
+            unsafe {
+                libc::sigaction(signal, &previous, std::ptr::null_mut());
+            }
+        }
+    }
+}
+
+/// Delivers a signal caught by the TUI to the process again, to be handled as if the TUI had never
+/// caught it. The listener must be dropped first.
+pub fn deliver(signal: i32) {
+    #[cfg(unix)]
+    // SAFETY: raising a signal on the current process has no memory safety implications.
+    unsafe {
+        libc::raise(signal);
+    }
+    #[cfg(not(unix))]
+    let _ = signal;
+}
+
+/// Stops the whole process until it receives SIGCONT, like a shell's Ctrl-Z would.
+pub fn suspend_process() {
+    #[cfg(unix)]
+    // SAFETY: raising a signal on the current process has no memory safety implications.
+    unsafe {
+        libc::raise(libc::SIGSTOP);
+    }
+}
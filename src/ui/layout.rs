@@ -0,0 +1,567 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::Deserialize;
+
+use crate::app::Tab;
+
+/// How often the layout file is checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+/// Terminal sizes below which pages fall back to a single column.
+const COMPACT_WIDTH: u16 = 100;
+const COMPACT_HEIGHT: u16 = 30;
+/// Terminal sizes from which pages get extra panels.
+const WIDE_WIDTH: u16 = 200;
+const WIDE_HEIGHT: u16 = 40;
+
+/// Terminal size classes, each with its own built-in layouts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    Compact,
+    Standard,
+    Wide,
+}
+
+impl Breakpoint {
+    pub fn of(size: Rect) -> Self {
+        if size.width < COMPACT_WIDTH || size.height < COMPACT_HEIGHT {
+            Breakpoint::Compact
+        } else if size.width >= WIDE_WIDTH && size.height >= WIDE_HEIGHT {
+            Breakpoint::Wide
+        } else {
+            Breakpoint::Standard
+        }
+    }
+}
+
+/// Every zone that can be placed on a page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    L2Logs,
+    L1Logs,
+    Network,
+    Cpu,
+    Memory,
+    Resources,
+    Storage,
+    Sync,
+    L1Sync,
+    Alerts,
+    Summary,
+    Blocks,
+    Throughput,
+    BlockTimes,
+    Reorgs,
+    L1Messages,
+    GasPrice,
+    Events,
+}
+
+impl Panel {
+    pub fn is_chart(self) -> bool {
+        matches!(self, Panel::Cpu | Panel::Memory | Panel::Network | Panel::Throughput | Panel::GasPrice)
+    }
+    pub fn is_logs(self) -> bool {
+        matches!(self, Panel::L2Logs | Panel::L1Logs)
+    }
+}
+
+/// Direction in which the focus moves between panels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Page layout: nested splits whose leaves are panels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    Split { direction: Direction, constraints: Vec<Constraint>, children: Vec<Node> },
+    Panel(Panel),
+}
+
+impl Node {
+    pub fn horizontal(constraints: Vec<Constraint>, children: Vec<Node>) -> Self {
+        Node::Split { direction: Direction::Horizontal, constraints, children }
+    }
+    pub fn vertical(constraints: Vec<Constraint>, children: Vec<Node>) -> Self {
+        Node::Split { direction: Direction::Vertical, constraints, children }
+    }
+    pub fn panels(&self) -> Vec<Panel> {
+        match self {
+            Node::Panel(panel) => vec![*panel],
+            Node::Split { children, .. } => children.iter().flat_map(Node::panels).collect(),
+        }
+    }
+    /// Resolves the area taken by each panel when the layout fills `area`.
+    pub fn areas(&self, area: Rect) -> Vec<(Panel, Rect)> {
+        match self {
+            Node::Panel(panel) => vec![(*panel, area)],
+            Node::Split { direction, constraints, children } => {
+                let zones = Layout::default().direction(*direction).constraints(constraints.clone()).split(area);
+                children.iter().zip(zones.iter()).flat_map(|(child, zone)| child.areas(*zone)).collect()
+            }
+        }
+    }
+}
+
+pub fn default_layout(tab: Tab) -> Node {
+    match tab {
+        Tab::Overview => Node::horizontal(
+            vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+            vec![
+                Node::vertical(
+                    vec![Constraint::Percentage(40), Constraint::Percentage(30), Constraint::Percentage(30)],
+                    vec![Node::Panel(Panel::L2Logs), Node::Panel(Panel::L1Logs), Node::Panel(Panel::Network)],
+                ),
+                Node::vertical(
+                    vec![
+                        Constraint::Ratio(1, 4),
+                        Constraint::Ratio(1, 4),
+                        Constraint::Ratio(1, 4),
+                        Constraint::Ratio(1, 4),
+                    ],
+                    vec![
+                        Node::Panel(Panel::Cpu),
+                        Node::Panel(Panel::Memory),
+                        Node::Panel(Panel::Resources),
+                        Node::Panel(Panel::Storage),
+                    ],
+                ),
+            ],
+        ),
+        Tab::Chain => Node::vertical(
+            vec![Constraint::Length(5), Constraint::Percentage(40), Constraint::Min(0)],
+            vec![
+                Node::horizontal(
+                    vec![Constraint::Percentage(60), Constraint::Percentage(40)],
+                    vec![Node::Panel(Panel::Sync), Node::Panel(Panel::L1Sync)],
+                ),
+                Node::Panel(Panel::Throughput),
+                Node::horizontal(
+                    vec![Constraint::Percentage(70), Constraint::Percentage(30)],
+                    vec![
+                        Node::Panel(Panel::Blocks),
+                        Node::vertical(
+                            vec![Constraint::Percentage(60), Constraint::Percentage(40)],
+                            vec![Node::Panel(Panel::BlockTimes), Node::Panel(Panel::Reorgs)],
+                        ),
+                    ],
+                ),
+            ],
+        ),
+        Tab::Logs => Node::horizontal(
+            vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+            vec![
+                Node::Panel(Panel::L2Logs),
+                // What L1 sent next to what the node says about it
+                Node::vertical(
+                    vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+                    vec![Node::Panel(Panel::L1Logs), Node::Panel(Panel::L1Messages)],
+                ),
+            ],
+        ),
+        Tab::System => Node::horizontal(
+            vec![Constraint::Percentage(60), Constraint::Percentage(40)],
+            vec![
+                Node::vertical(
+                    vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+                    vec![Node::Panel(Panel::Cpu), Node::Panel(Panel::Memory)],
+                ),
+                // Sync speed next to what it costs
+                Node::vertical(
+                    vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+                    vec![Node::Panel(Panel::Resources), Node::Panel(Panel::Throughput)],
+                ),
+            ],
+        ),
+        Tab::Storage => Node::vertical(
+            vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+            vec![Node::Panel(Panel::Storage), Node::Panel(Panel::Resources)],
+        ),
+        Tab::Network => Node::horizontal(
+            vec![Constraint::Percentage(60), Constraint::Percentage(40)],
+            vec![Node::Panel(Panel::Network), Node::Panel(Panel::GasPrice)],
+        ),
+        Tab::Alerts => Node::Panel(Panel::Alerts),
+        Tab::Events => Node::Panel(Panel::Events),
+    }
+}
+
+/// Single column layouts, with the key numbers in place of the overview charts.
+pub fn compact_layout(tab: Tab) -> Node {
+    match tab {
+        Tab::Overview => Node::vertical(
+            vec![Constraint::Length(9), Constraint::Min(0)],
+            vec![Node::Panel(Panel::Summary), Node::Panel(Panel::L2Logs)],
+        ),
+        tab => {
+            let panels = default_layout(tab).panels();
+            let constraints = panels.iter().map(|_| Constraint::Ratio(1, panels.len() as u32)).collect();
+            Node::vertical(constraints, panels.into_iter().map(Node::Panel).collect())
+        }
+    }
+}
+
+/// Layouts with extra panels for pages that have room for them.
+pub fn wide_layout(tab: Tab) -> Option<Node> {
+    match tab {
+        Tab::Overview => Some(Node::horizontal(
+            vec![Constraint::Percentage(35), Constraint::Percentage(35), Constraint::Percentage(30)],
+            vec![
+                Node::vertical(
+                    vec![Constraint::Percentage(40), Constraint::Percentage(30), Constraint::Percentage(30)],
+                    vec![Node::Panel(Panel::L2Logs), Node::Panel(Panel::L1Logs), Node::Panel(Panel::Network)],
+                ),
+                Node::vertical(
+                    vec![
+                        Constraint::Ratio(1, 4),
+                        Constraint::Ratio(1, 4),
+                        Constraint::Ratio(1, 4),
+                        Constraint::Ratio(1, 4),
+                    ],
+                    vec![
+                        Node::Panel(Panel::Cpu),
+                        Node::Panel(Panel::Memory),
+                        Node::Panel(Panel::Resources),
+                        Node::Panel(Panel::Storage),
+                    ],
+                ),
+                Node::vertical(
+                    vec![Constraint::Percentage(40), Constraint::Percentage(60)],
+                    vec![Node::Panel(Panel::Sync), Node::Panel(Panel::Alerts)],
+                ),
+            ],
+        )),
+        _ => None,
+    }
+}
+
+/// Page layouts, taken from the user's layout file when there is one.
+///
+/// The file maps page names to a tree of splits, for instance in TOML:
+///
+/// ```toml
+/// [pages.system]
+/// split = "horizontal"
+/// constraints = ["60%", "40%"]
+/// children = [{ panel = "cpu" }, { panel = "memory" }]
+/// ```
+///
+/// Constraints are written `"50%"`, `"1/3"`, `"10"` (cells), `"min:10"`, `"max:10"` or `"fill:1"`.
+/// Pages missing from the file keep their built-in layout. A `.json` file is read as JSON. Small
+/// terminals always get the built-in single column layouts.
+pub struct Layouts {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+    pages: HashMap<Tab, Node>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayoutFile {
+    #[serde(default)]
+    pages: HashMap<Tab, NodeSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NodeSpec {
+    Panel { panel: Panel },
+    Split { split: SplitDirection, constraints: Vec<String>, children: Vec<NodeSpec> },
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl Layouts {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let mut layouts = Self {
+            path,
+            modified: None,
+            last_check: Instant::now(),
+            pages: HashMap::new(),
+            error: None,
+        };
+        layouts.load();
+        layouts
+    }
+    pub fn page(&self, tab: Tab, breakpoint: Breakpoint) -> Node {
+        if breakpoint == Breakpoint::Compact {
+            return compact_layout(tab);
+        }
+        if let Some(node) = self.pages.get(&tab) {
+            return node.clone();
+        }
+        match breakpoint {
+            Breakpoint::Wide => wide_layout(tab).unwrap_or_else(|| default_layout(tab)),
+            _ => default_layout(tab),
+        }
+    }
+    /// Reloads the layout file if it was modified since it was last read.
+    pub fn reload_if_changed(&mut self) {
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+        let Some(path) = &self.path else {
+            return;
+        };
+        let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        if modified != self.modified {
+            self.load();
+        }
+    }
+    fn load(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        self.modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        match read_layout_file(path) {
+            Ok(pages) => {
+                self.pages = pages;
+                self.error = None;
+            }
+            Err(err) => {
+                self.pages.clear();
+                self.error = Some(format!("{}: {}", path.display(), err));
+            }
+        }
+    }
+}
+
+fn read_layout_file(path: &PathBuf) -> Result<HashMap<Tab, Node>, String> {
+    let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    parse_layout_file(&content, path.extension().is_some_and(|extension| extension == "json"))
+}
+
+/// Pages of a layout file in JSON, or in TOML otherwise.
+fn parse_layout_file(content: &str, json: bool) -> Result<HashMap<Tab, Node>, String> {
+    let file: LayoutFile = if json {
+        serde_json::from_str(content).map_err(|err| err.to_string())?
+    } else {
+        toml::from_str(content).map_err(|err| err.to_string())?
+    };
+    file.pages
+        .into_iter()
+        .map(|(tab, spec)| {
+            let node = build_node(spec).map_err(|err| format!("page {}: {}", tab.title(), err))?;
+            let mut seen = HashSet::new();
+            if let Some(panel) = node.panels().into_iter().find(|panel| !seen.insert(*panel)) {
+                return Err(format!("page {}: panel {:?} is placed twice", tab.title(), panel));
+            }
+            Ok((tab, node))
+        })
+        .collect()
+}
+
+fn build_node(spec: NodeSpec) -> Result<Node, String> {
+    match spec {
+        NodeSpec::Panel { panel } => Ok(Node::Panel(panel)),
+        NodeSpec::Split { split, constraints, children } => {
+            if children.is_empty() {
+                return Err("a split needs at least one child".to_string());
+            }
+            if constraints.len() != children.len() {
+                return Err(format!("{} constraints for {} children", constraints.len(), children.len()));
+            }
+            let direction = match split {
+                SplitDirection::Horizontal => Direction::Horizontal,
+                SplitDirection::Vertical => Direction::Vertical,
+            };
+            Ok(Node::Split {
+                direction,
+                constraints: constraints.iter().map(|raw| parse_constraint(raw)).collect::<Result<_, _>>()?,
+                children: children.into_iter().map(build_node).collect::<Result<_, _>>()?,
+            })
+        }
+    }
+}
+
+fn parse_constraint(raw: &str) -> Result<Constraint, String> {
+    let invalid = || format!("invalid constraint {:?}", raw);
+    let number = |value: &str| value.trim().parse::<u16>().map_err(|_| invalid());
+    let raw = raw.trim();
+    if let Some(percentage) = raw.strip_suffix('%') {
+        let percentage = number(percentage)?;
+        return if percentage <= 100 { Ok(Constraint::Percentage(percentage)) } else { Err(invalid()) };
+    }
+    if let Some((numerator, denominator)) = raw.split_once('/') {
+        let (numerator, denominator) = (number(numerator)?, number(denominator)?);
+        return if denominator > 0 {
+            Ok(Constraint::Ratio(numerator as u32, denominator as u32))
+        } else {
+            Err(invalid())
+        };
+    }
+    if let Some(min) = raw.strip_prefix("min:") {
+        return Ok(Constraint::Min(number(min)?));
+    }
+    if let Some(max) = raw.strip_prefix("max:") {
+        return Ok(Constraint::Max(number(max)?));
+    }
+    if let Some(fill) = raw.strip_prefix("fill:") {
+        return Ok(Constraint::Fill(number(fill)?));
+    }
+    Ok(Constraint::Length(number(raw)?))
+}
+
+/// Finds the panel closest to `from` in `direction`, preferring panels facing it.
+pub fn neighbour(areas: &[(Panel, Rect)], from: Panel, direction: FocusDirection) -> Option<Panel> {
+    let (_, origin) = areas.iter().find(|(panel, _)| *panel == from)?;
+    let center = |area: &Rect| (area.x as i32 + area.width as i32 / 2, area.y as i32 + area.height as i32 / 2);
+    let (ox, oy) = center(origin);
+    areas
+        .iter()
+        .filter(|(panel, area)| {
+            *panel != from
+                && match direction {
+                    FocusDirection::Left => area.right() <= origin.left(),
+                    FocusDirection::Right => area.left() >= origin.right(),
+                    FocusDirection::Up => area.bottom() <= origin.top(),
+                    FocusDirection::Down => area.top() >= origin.bottom(),
+                }
+        })
+        .min_by_key(|(_, area)| {
+            let (x, y) = center(area);
+            match direction {
+                FocusDirection::Left | FocusDirection::Right => ((x - ox).abs(), (y - oy).abs()),
+                FocusDirection::Up | FocusDirection::Down => ((y - oy).abs(), (x - ox).abs()),
+            }
+        })
+        .map(|(panel, _)| *panel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breakpoint_follows_both_dimensions() {
+        assert_eq!(Breakpoint::of(Rect::new(0, 0, 99, 50)), Breakpoint::Compact);
+        assert_eq!(Breakpoint::of(Rect::new(0, 0, 250, 29)), Breakpoint::Compact);
+        assert_eq!(Breakpoint::of(Rect::new(0, 0, 100, 30)), Breakpoint::Standard);
+        assert_eq!(Breakpoint::of(Rect::new(0, 0, 250, 39)), Breakpoint::Standard);
+        assert_eq!(Breakpoint::of(Rect::new(0, 0, 199, 60)), Breakpoint::Standard);
+        assert_eq!(Breakpoint::of(Rect::new(0, 0, 200, 40)), Breakpoint::Wide);
+    }
+
+    #[test]
+    fn built_in_layouts_place_each_panel_once() {
+        let layouts = Layouts::new(None);
+        for tab in Tab::ALL {
+            for breakpoint in [Breakpoint::Compact, Breakpoint::Standard, Breakpoint::Wide] {
+                let panels = layouts.page(tab, breakpoint).panels();
+                let unique: HashSet<_> = panels.iter().collect();
+                assert_eq!(unique.len(), panels.len(), "{:?} on {:?}", tab, breakpoint);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_constraint_reads_every_kind() {
+        assert_eq!(parse_constraint("30%"), Ok(Constraint::Percentage(30)));
+        assert_eq!(parse_constraint(" 1/3 "), Ok(Constraint::Ratio(1, 3)));
+        assert_eq!(parse_constraint("min:5"), Ok(Constraint::Min(5)));
+        assert_eq!(parse_constraint("max:20"), Ok(Constraint::Max(20)));
+        assert_eq!(parse_constraint("fill:2"), Ok(Constraint::Fill(2)));
+        assert_eq!(parse_constraint("12"), Ok(Constraint::Length(12)));
+    }
+
+    #[test]
+    fn parse_constraint_rejects_invalid_ones() {
+        for raw in ["101%", "1/0", "min:", "half", "-3", "2.5"] {
+            assert!(parse_constraint(raw).is_err(), "{:?} was accepted", raw);
+        }
+    }
+
+    #[test]
+    fn layout_file_builds_nested_splits() {
+        let toml = r#"
+            [pages.system]
+            split = "horizontal"
+            constraints = ["40%", "fill:1"]
+
+            [[pages.system.children]]
+            panel = "cpu"
+
+            [[pages.system.children]]
+            split = "vertical"
+            constraints = ["50%", "50%"]
+            children = [{ panel = "memory" }, { panel = "resources" }]
+        "#;
+        let expected = Node::horizontal(
+            vec![Constraint::Percentage(40), Constraint::Fill(1)],
+            vec![
+                Node::Panel(Panel::Cpu),
+                Node::vertical(
+                    vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+                    vec![Node::Panel(Panel::Memory), Node::Panel(Panel::Resources)],
+                ),
+            ],
+        );
+        let pages = parse_layout_file(toml, false).unwrap();
+        assert_eq!(pages.get(&Tab::System), Some(&expected));
+
+        let json = r#"{ "pages": { "network": { "panel": "network" } } }"#;
+        let pages = parse_layout_file(json, true).unwrap();
+        assert_eq!(pages.get(&Tab::Network), Some(&Node::Panel(Panel::Network)));
+    }
+
+    #[test]
+    fn layout_file_rejects_inconsistent_pages() {
+        let mismatched = r#"{ "pages": { "system": { "split": "vertical", "constraints": ["50%"],
+            "children": [{ "panel": "cpu" }, { "panel": "memory" }] } } }"#;
+        assert!(parse_layout_file(mismatched, true).unwrap_err().contains("1 constraints for 2 children"));
+        let twice = r#"{ "pages": { "system": { "split": "vertical", "constraints": ["50%", "50%"],
+            "children": [{ "panel": "cpu" }, { "panel": "cpu" }] } } }"#;
+        assert!(parse_layout_file(twice, true).unwrap_err().contains("placed twice"));
+        assert!(parse_layout_file(r#"{ "pages": { "system": { "panel": "gpu" } } }"#, true).is_err());
+        assert!(parse_layout_file(r#"{ "tabs": {} }"#, true).is_err());
+    }
+
+    #[test]
+    fn neighbour_moves_to_the_adjacent_panel() {
+        let areas = [
+            (Panel::Cpu, Rect::new(0, 0, 50, 10)),
+            (Panel::Memory, Rect::new(50, 0, 50, 10)),
+            (Panel::Network, Rect::new(0, 10, 50, 10)),
+            (Panel::Storage, Rect::new(50, 10, 50, 10)),
+        ];
+        assert_eq!(neighbour(&areas, Panel::Cpu, FocusDirection::Right), Some(Panel::Memory));
+        assert_eq!(neighbour(&areas, Panel::Cpu, FocusDirection::Down), Some(Panel::Network));
+        assert_eq!(neighbour(&areas, Panel::Storage, FocusDirection::Up), Some(Panel::Memory));
+        assert_eq!(neighbour(&areas, Panel::Storage, FocusDirection::Left), Some(Panel::Network));
+    }
+
+    #[test]
+    fn neighbour_stops_at_the_edges() {
+        let areas = [(Panel::Cpu, Rect::new(0, 0, 50, 10)), (Panel::Memory, Rect::new(50, 0, 50, 10))];
+        assert_eq!(neighbour(&areas, Panel::Cpu, FocusDirection::Left), None);
+        assert_eq!(neighbour(&areas, Panel::Cpu, FocusDirection::Up), None);
+        assert_eq!(neighbour(&areas, Panel::Memory, FocusDirection::Right), None);
+        assert_eq!(neighbour(&areas, Panel::Alerts, FocusDirection::Right), None);
+    }
+
+    #[test]
+    fn neighbour_prefers_the_panel_facing_it() {
+        let areas = [
+            (Panel::Cpu, Rect::new(0, 0, 50, 20)),
+            (Panel::Memory, Rect::new(50, 0, 50, 5)),
+            (Panel::Network, Rect::new(50, 5, 50, 15)),
+        ];
+        assert_eq!(neighbour(&areas, Panel::Cpu, FocusDirection::Right), Some(Panel::Network));
+    }
+}
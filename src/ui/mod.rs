@@ -1,2 +1,4 @@
+pub mod layout;
 pub mod render;
+pub mod theme;
 mod widgets;
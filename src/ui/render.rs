@@ -1,65 +1,381 @@
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
 use anyhow::Result;
+use crossterm::cursor::Show;
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::execute;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::prelude::Frame;
-use ratatui::style::Color;
-use ratatui::widgets::{Block, Borders};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Tabs, Wrap};
 
-use crate::app::App;
+use crate::app::{App, Tab};
+use crate::keymap::{Action, Context};
+use crate::radar::TargetStatus;
+use crate::ui::layout::{Breakpoint, Panel};
+use crate::ui::widgets::alerts::*;
+use crate::ui::widgets::blocks::*;
 use crate::ui::widgets::cpu::*;
+use crate::ui::widgets::events::*;
+use crate::ui::widgets::gas::*;
+use crate::ui::widgets::inspector::*;
+use crate::ui::widgets::l1::*;
 use crate::ui::widgets::logs::*;
 use crate::ui::widgets::memory::*;
+use crate::ui::widgets::messages::*;
 use crate::ui::widgets::network::*;
+use crate::ui::widgets::reorgs::*;
+use crate::ui::widgets::resources::*;
 use crate::ui::widgets::storage::*;
-use crate::ui::widgets::utils::render_zone;
+use crate::ui::widgets::summary::*;
+use crate::ui::widgets::syncing::*;
+use crate::ui::widgets::throughput::*;
+use crate::ui::widgets::utils::{highlight_border, render_zone};
+use crate::ui::widgets::Detail;
+
+/// Smallest terminal in which the dashboard is drawn.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
 
 pub fn ui(app: &App, frame: &mut Frame) {
-    let outline = Block::new()
+    let size = frame.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        render_too_small(frame, app);
+        return;
+    }
+    let hint = match app.keymap.key_for(Action::Help) {
+        Some(key) => format!(" Deoxys-TUI v0.1.0 (Press {} for help) ", key),
+        None => " Deoxys-TUI v0.1.0 ".to_string(),
+    };
+    let mut outline = Block::new()
         .borders(Borders::ALL)
-        .title(" Deoxys-TUI v0.1.0 (Press q to quit) ")
-        .title_style(Color::Magenta)
-        .title_alignment(Alignment::Center);
+        .title(hint)
+        .title_style(app.theme().title)
+        .title_alignment(Alignment::Center)
+        .title(Title::from(target_status(app)).position(Position::Bottom).alignment(Alignment::Center));
+    if app.is_paused() {
+        let badge = Line::from(" PAUSED ").fg(app.theme().warning).bold().reversed();
+        outline = outline.title(Title::from(badge).alignment(Alignment::Right));
+    }
+    if matches!(&app.data.reference, Some(Ok(comparison)) if comparison.diverged()) {
+        let badge = Line::from(" DIVERGED FROM REFERENCE ").fg(app.theme().critical).bold().reversed();
+        outline = outline.title(Title::from(badge).alignment(Alignment::Left));
+    }
     frame.render_widget(outline, frame.size());
 
-    let node0 = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(frame.size().inner(&Margin::new(2, 1)));
+    if app.inspector.as_ref().is_some_and(|inspector| !inspector.is_popup()) {
+        render_inspector(frame, app, frame.size().inner(&Margin::new(2, 1)));
+    } else if app.zoomed {
+        render_panel(frame, app, app.focus, frame.size().inner(&Margin::new(2, 1)), Detail::Full);
+    } else {
+        let zones = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+            .split(frame.size().inner(&Margin::new(2, 1)));
+        render_tabs(frame, app, zones[0]);
 
-    let left = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(30), Constraint::Percentage(30)])
-        .split(node0[0]);
-    let right = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
-        .split(node0[1]);
+        for (panel, area) in app.page().areas(zones[1]) {
+            render_panel(frame, app, panel, area, Detail::Compact);
+            if panel == app.focus {
+                highlight_border(frame, area, app.theme());
+            }
+        }
+    }
+
+    if app.inspector.as_ref().is_some_and(|inspector| inspector.is_popup()) {
+        let size = frame.size();
+        let popup = centered(size, size.width * 4 / 5, size.height * 4 / 5);
+        frame.render_widget(Clear, popup);
+        render_inspector(frame, app, popup);
+    }
+
+    if app.show_help {
+        render_help(frame, app);
+    } else if let Some(error) = &app.layouts.error {
+        render_error_popup(frame, app, "Invalid layout, using the built-in one (Esc to dismiss)", error);
+    } else if let Some(error) = &app.themes.error {
+        render_error_popup(frame, app, "Invalid theme, using the built-in one (Esc to dismiss)", error);
+    } else if let Some(error) = &app.keymap.error {
+        render_error_popup(frame, app, "Invalid keymap, using the default one (Esc to dismiss)", error);
+    } else if let Some(error) = &app.command_error {
+        render_error_popup(frame, app, "Invalid command (Esc to dismiss)", error);
+    }
+    if let Some(prompt) = &app.prompt {
+        render_prompt(frame, app, prompt);
+    }
+}
+
+/// Command line over the bottom of the page, with the cursor after the typed text.
+fn render_prompt(frame: &mut Frame, app: &App, prompt: &str) {
+    let size = frame.size();
+    let area = Rect::new(size.x + 2, size.bottom().saturating_sub(2), size.width.saturating_sub(4), 1);
+    let line = Line::from(vec![
+        Span::styled(":", Style::new().fg(app.theme().emphasis).bold()),
+        Span::styled(prompt, Style::new().fg(app.theme().text)),
+    ]);
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(line), area);
+    frame.set_cursor((area.x + 1 + prompt.chars().count() as u16).min(area.right().saturating_sub(1)), area.y);
+}
+
+fn render_too_small(frame: &mut Frame, app: &App) {
+    let size = frame.size();
+    let message = vec![
+        Line::from("Terminal too small").fg(app.theme().warning).bold(),
+        Line::from(format!("{}x{}, needs {}x{}", size.width, size.height, MIN_WIDTH, MIN_HEIGHT)).fg(app.theme().muted),
+    ];
+    let area = centered(size, size.width, message.len() as u16);
+    frame.render_widget(Paragraph::new(message).alignment(Alignment::Center).wrap(Wrap { trim: true }), area);
+}
 
-    render_zone(frame, right[0], "CPU");
-    render_cpu(frame, app, right[0].inner(&Margin::new(1, 1)));
+/// Area of the given size centered in `area`, shrunk to fit.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
 
-    render_zone(frame, right[1], "Memory");
-    render_memory(frame, app, right[1].inner(&Margin::new(1, 1)));
+/// Bindings usable where the help was opened from, the help itself excepted.
+fn render_help(frame: &mut Frame, app: &App) {
+    let theme = app.theme();
+    let context = if app.inspector.is_some() {
+        Context::Inspector
+    } else if app.zoomed {
+        Context::Zoomed
+    } else {
+        Context::Dashboard
+    };
+    let bindings = app.keymap.help(context);
+    let keys_width = bindings.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = bindings
+        .iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(format!("{:>width$}  ", keys, width = keys_width), Style::new().fg(theme.emphasis).bold()),
+                Span::styled(*description, Style::new().fg(theme.text)),
+            ])
+        })
+        .collect();
+    if app.mouse_capture {
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Click a panel to focus it, click a focused chart to zoom").fg(theme.muted));
+        lines.push(Line::raw("Scroll over logs, hover a chart to read its values").fg(theme.muted));
+    }
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+    let popup = centered(frame.size(), width, lines.len() as u16 + 2);
+    let title = match context {
+        Context::Inspector => "Keys (inspector)",
+        Context::Zoomed => "Keys (zoomed panel)",
+        _ => "Keys",
+    };
+    let paragraph = Paragraph::new(lines).block(
+        Block::new()
+            .title(title)
+            .title_style(Style::new().fg(theme.title).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(theme.focus))
+            .padding(Padding::horizontal(1)),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
+}
 
-    render_zone(frame, right[2], "Storage");
-    render_storage(frame, app, right[2].inner(&Margin::new(1, 1)));
+fn render_error_popup(frame: &mut Frame, app: &App, title: &str, message: &str) {
+    let theme = app.theme();
+    let area = frame.size();
+    let width = (area.width * 3 / 5).max(title.len() as u16 + 4);
+    let popup = centered(area, width, (area.height / 3).max(5));
+    let paragraph = Paragraph::new(message).wrap(Wrap { trim: true }).style(Style::new().fg(theme.warning)).block(
+        Block::new()
+            .title(title)
+            .title_style(Style::new().fg(theme.emphasis).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(theme.critical)),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
+}
 
-    render_zone(frame, left[2], "Network");
-    render_network_graph(frame, app, left[2]);
-    render_l2_logs(frame, app, left[0]);
-    render_l1_logs(frame, app, left[1]);
+/// Area left to the panels of a page in a frame of the given size.
+pub fn page_area(frame_area: Rect) -> Rect {
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+        .split(frame_area.inner(&Margin::new(2, 1)));
+    zones[1]
 }
 
+fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    let alerts = active_alerts(app).len();
+    // Only the current tab is named when they would not all fit
+    let compact = Breakpoint::of(frame.size()) == Breakpoint::Compact;
+    let titles: Vec<Line> = Tab::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, tab)| {
+            let title = if compact && *tab != app.tab { String::new() } else { format!(" {}", tab.title()) };
+            match tab {
+                Tab::Alerts if alerts > 0 => {
+                    Line::from(format!("{}{} ({})", i + 1, title, alerts)).fg(app.theme().critical)
+                }
+                _ => Line::from(format!("{}{}", i + 1, title)),
+            }
+        })
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(app.tab.index())
+        .highlight_style(Style::new().fg(app.theme().tab_highlight).bold().reversed());
+    frame.render_widget(tabs, area);
+}
+
+fn render_panel(frame: &mut Frame, app: &App, panel: Panel, area: Rect, detail: Detail) {
+    match panel {
+        Panel::L2Logs => render_l2_logs(frame, app, area, detail),
+        Panel::L1Logs => render_l1_logs(frame, app, area, detail),
+        Panel::Network => {
+            render_zone(frame, area, "Network");
+            render_network_graph(frame, app, area, detail);
+        }
+        Panel::Cpu => {
+            render_zone(frame, area, &cpu_title(app));
+            render_cpu(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Memory => {
+            render_zone(frame, area, "Memory");
+            render_memory(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Resources => {
+            render_zone(frame, area, "Resources");
+            render_resources(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Storage => {
+            render_zone(frame, area, "Storage");
+            render_storage(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Sync => render_sync(frame, app, area),
+        Panel::L1Sync => {
+            render_zone(frame, area, "L1 state");
+            render_l1_sync(frame, app, area.inner(&Margin::new(1, 1)));
+        }
+        Panel::Alerts => render_alerts(frame, app, area),
+        Panel::Summary => {
+            render_zone(frame, area, "Summary");
+            render_summary(frame, app, area.inner(&Margin::new(1, 1)));
+        }
+        Panel::Throughput => {
+            render_zone(frame, area, &throughput_title(app));
+            render_throughput(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::BlockTimes => {
+            render_zone(frame, area, &block_times_title(app));
+            render_block_times(frame, app, area.inner(&Margin::new(1, 1)));
+        }
+        Panel::L1Messages => {
+            render_zone(frame, area, &l1_messages_title(app));
+            render_l1_messages(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::GasPrice => {
+            render_zone(frame, area, &gas_price_title(app));
+            render_gas_price(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Events => {
+            render_zone(frame, area, &events_title(app));
+            render_events(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Reorgs => {
+            render_zone(frame, area, &reorgs_title(app));
+            render_reorgs(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+        Panel::Blocks => {
+            render_zone(frame, area, &blocks_title(app));
+            render_blocks(frame, app, area.inner(&Margin::new(1, 1)), detail);
+        }
+    }
+}
+
+fn target_status(app: &App) -> Line<'static> {
+    let theme = app.theme();
+    match app.data.target_status {
+        TargetStatus::Running { pid, processes: 1 } => {
+            Line::from(format!(" Monitoring pid {} ", pid)).fg(theme.muted)
+        }
+        TargetStatus::Running { pid, processes } => {
+            Line::from(format!(" Monitoring pid {} ({} processes) ", pid, processes)).fg(theme.muted)
+        }
+        TargetStatus::Restarted { pid, .. } => {
+            Line::from(format!(" Process restarted, reattached to pid {} ", pid)).fg(theme.warning).bold()
+        }
+        TargetStatus::Exited => {
+            Line::from(" Process exited, waiting for it to come back ").fg(theme.critical).bold()
+        }
+    }
+}
+
+/// Whether the terminal is currently in raw mode on the alternate screen.
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Whether mouse events are captured, kept across suspends.
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
 pub fn startup() -> Result<()> {
     enable_raw_mode()?;
     execute!(std::io::stdout(), EnterAlternateScreen)?;
+    if MOUSE_CAPTURE.load(Ordering::SeqCst) {
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+    }
+    TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
     Ok(())
 }
 
+/// Captures the mouse, or gives it back to the terminal for text selection.
+pub fn set_mouse_capture(enabled: bool) -> Result<()> {
+    if MOUSE_CAPTURE.swap(enabled, Ordering::SeqCst) == enabled || !is_active() {
+        return Ok(());
+    }
+    if enabled {
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+    } else {
+        execute!(std::io::stdout(), DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
+pub fn mouse_capture() -> bool {
+    MOUSE_CAPTURE.load(Ordering::SeqCst)
+}
+
+/// Restores the terminal, does nothing if it already was.
 pub fn shutdown() -> Result<()> {
-    execute!(std::io::stdout(), LeaveAlternateScreen)?;
+    if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    if MOUSE_CAPTURE.load(Ordering::SeqCst) {
+        execute!(std::io::stdout(), DisableMouseCapture)?;
+    }
+    execute!(std::io::stdout(), LeaveAlternateScreen, Show)?;
     disable_raw_mode()?;
     std::env::set_var("RUST_LOG", "TRACE");
     Ok(())
 }
+
+pub fn is_active() -> bool {
+    TERMINAL_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Restores the terminal before any panic message is printed, so that it is not lost on the
+/// alternate screen. This applies to panics from any thread, the TUI then stops. The hook is
+/// installed once per process, however many times the TUI is started.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = shutdown();
+            hook(info);
+        }));
+    });
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+/// Named colors used by every widget, so that none of them hardcodes a color.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: String,
+    pub title: Color,
+    pub text: Color,
+    pub muted: Color,
+    pub emphasis: Color,
+    pub focus: Color,
+    pub tab_highlight: Color,
+    pub cpu: Color,
+    pub memory: Color,
+    pub rx: Color,
+    pub tx: Color,
+    pub measured: Color,
+    pub gauge_low: Color,
+    pub gauge_medium: Color,
+    pub gauge_high: Color,
+    pub gauge_label: Color,
+    pub warning: Color,
+    pub critical: Color,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            title: Color::Magenta,
+            text: Color::Green,
+            muted: Color::Gray,
+            emphasis: Color::White,
+            focus: Color::Yellow,
+            tab_highlight: Color::Magenta,
+            cpu: Color::Cyan,
+            memory: Color::Magenta,
+            rx: Color::Green,
+            tx: Color::LightRed,
+            measured: Color::Gray,
+            gauge_low: Color::Green,
+            gauge_medium: Color::Rgb(255, 128, 0),
+            gauge_high: Color::Red,
+            gauge_label: Color::Rgb(20, 20, 20),
+            warning: Color::Yellow,
+            critical: Color::Red,
+        }
+    }
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            title: Color::Magenta,
+            text: Color::Rgb(0, 110, 0),
+            muted: Color::DarkGray,
+            emphasis: Color::Black,
+            focus: Color::Blue,
+            tab_highlight: Color::Blue,
+            cpu: Color::Blue,
+            memory: Color::Magenta,
+            rx: Color::Rgb(0, 130, 0),
+            tx: Color::Red,
+            measured: Color::DarkGray,
+            gauge_low: Color::Rgb(0, 150, 0),
+            gauge_medium: Color::Rgb(200, 100, 0),
+            gauge_high: Color::Red,
+            gauge_label: Color::White,
+            warning: Color::Rgb(170, 110, 0),
+            critical: Color::Red,
+        }
+    }
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            title: Color::LightYellow,
+            text: Color::White,
+            muted: Color::White,
+            emphasis: Color::White,
+            focus: Color::LightYellow,
+            tab_highlight: Color::LightYellow,
+            cpu: Color::LightCyan,
+            memory: Color::LightMagenta,
+            rx: Color::LightGreen,
+            tx: Color::LightRed,
+            measured: Color::White,
+            gauge_low: Color::LightGreen,
+            gauge_medium: Color::LightYellow,
+            gauge_high: Color::LightRed,
+            gauge_label: Color::Black,
+            warning: Color::LightYellow,
+            critical: Color::LightRed,
+        }
+    }
+    /// Okabe-Ito palette, distinguishable with the common forms of color blindness.
+    pub fn colorblind() -> Self {
+        Self {
+            name: "colorblind".to_string(),
+            title: Color::Rgb(204, 121, 167),
+            text: Color::White,
+            muted: Color::Gray,
+            emphasis: Color::White,
+            focus: Color::Rgb(240, 228, 66),
+            tab_highlight: Color::Rgb(86, 180, 233),
+            cpu: Color::Rgb(86, 180, 233),
+            memory: Color::Rgb(204, 121, 167),
+            rx: Color::Rgb(0, 158, 115),
+            tx: Color::Rgb(230, 159, 0),
+            measured: Color::Gray,
+            gauge_low: Color::Rgb(0, 158, 115),
+            gauge_medium: Color::Rgb(230, 159, 0),
+            gauge_high: Color::Rgb(213, 94, 0),
+            gauge_label: Color::Black,
+            warning: Color::Rgb(230, 159, 0),
+            critical: Color::Rgb(213, 94, 0),
+        }
+    }
+    /// Terminal default colors only, used when `NO_COLOR` is set.
+    pub fn monochrome() -> Self {
+        Self {
+            name: "monochrome".to_string(),
+            title: Color::Reset,
+            text: Color::Reset,
+            muted: Color::Reset,
+            emphasis: Color::Reset,
+            focus: Color::Reset,
+            tab_highlight: Color::Reset,
+            cpu: Color::Reset,
+            memory: Color::Reset,
+            rx: Color::Reset,
+            tx: Color::Reset,
+            measured: Color::Reset,
+            gauge_low: Color::Reset,
+            gauge_medium: Color::Reset,
+            gauge_high: Color::Reset,
+            gauge_label: Color::Reset,
+            warning: Color::Reset,
+            critical: Color::Reset,
+        }
+    }
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "colorblind" => Some(Self::colorblind()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+    fn slot_mut(&mut self, slot: &str) -> Option<&mut Color> {
+        match slot {
+            "title" => Some(&mut self.title),
+            "text" => Some(&mut self.text),
+            "muted" => Some(&mut self.muted),
+            "emphasis" => Some(&mut self.emphasis),
+            "focus" => Some(&mut self.focus),
+            "tab_highlight" => Some(&mut self.tab_highlight),
+            "cpu" => Some(&mut self.cpu),
+            "memory" => Some(&mut self.memory),
+            "rx" => Some(&mut self.rx),
+            "tx" => Some(&mut self.tx),
+            "measured" => Some(&mut self.measured),
+            "gauge_low" => Some(&mut self.gauge_low),
+            "gauge_medium" => Some(&mut self.gauge_medium),
+            "gauge_high" => Some(&mut self.gauge_high),
+            "gauge_label" => Some(&mut self.gauge_label),
+            "warning" => Some(&mut self.warning),
+            "critical" => Some(&mut self.critical),
+            _ => None,
+        }
+    }
+}
+
+/// Theme file: a built-in theme to start from and the slots to override, for instance:
+///
+/// ```toml
+/// base = "light"
+///
+/// [colors]
+/// cpu = "#0072b2"
+/// critical = "light-red"
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+}
+
+fn read_theme_file(path: &Path) -> Result<Theme, String> {
+    let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let file: ThemeFile = toml::from_str(&content).map_err(|err| err.to_string())?;
+    let base = file.base.unwrap_or_else(|| "dark".to_string());
+    let mut theme = Theme::builtin(&base).ok_or_else(|| format!("unknown base theme {:?}", base))?;
+    theme.name = "custom".to_string();
+    for (slot, color) in file.colors {
+        let color = Color::from_str(&color).map_err(|_| format!("invalid color {:?} for {}", color, slot))?;
+        *theme.slot_mut(&slot).ok_or_else(|| format!("unknown color slot {:?}", slot))? = color;
+    }
+    Ok(theme)
+}
+
+/// Themes the user can cycle through at runtime.
+pub struct Themes {
+    themes: Vec<Theme>,
+    current: usize,
+    pub error: Option<String>,
+}
+
+impl Themes {
+    /// Starts on the custom theme when one is given, or without colors when `NO_COLOR` is set.
+    pub fn new(path: Option<&Path>) -> Self {
+        let mut themes = vec![Theme::dark(), Theme::light(), Theme::high_contrast(), Theme::colorblind()];
+        let mut current = 0;
+        let mut error = None;
+        if let Some(path) = path {
+            match read_theme_file(path) {
+                Ok(theme) => {
+                    themes.push(theme);
+                    current = themes.len() - 1;
+                }
+                Err(err) => error = Some(format!("{}: {}", path.display(), err)),
+            }
+        }
+        themes.push(Theme::monochrome());
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            current = themes.len() - 1;
+        }
+        Self { themes, current, error }
+    }
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+    pub fn cycle(&mut self) {
+        self.current = (self.current + 1) % self.themes.len();
+    }
+}
@@ -0,0 +1,95 @@
+use ratatui::layout::{Margin, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use super::utils::render_zone;
+use crate::app::App;
+use crate::radar::TargetStatus;
+
+/// Usage ratio above which a resource is reported.
+const USAGE_ALERT: f64 = 0.8;
+/// Blocks behind the reference node above which the node is reported as lagging.
+const REFERENCE_LAG_ALERT: i64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+/// Conditions currently worth the operator's attention, most severe first.
+pub fn active_alerts(app: &App) -> Vec<(Severity, String)> {
+    let mut alerts = Vec::new();
+    match app.data.target_status {
+        TargetStatus::Exited => alerts.push((Severity::Critical, "Monitored process exited".to_string())),
+        TargetStatus::Restarted { pid, .. } => {
+            alerts.push((Severity::Warning, format!("Monitored process restarted as pid {}", pid)))
+        }
+        TargetStatus::Running { .. } => {}
+    }
+    if app.data.cpu_sampler.is_stale() {
+        alerts.push((Severity::Warning, "CPU usage is not being sampled".to_string()));
+    }
+    if let Some(resources) = &app.data.resources {
+        if let Some(limit) = resources.fd_soft_limit.filter(|limit| *limit > 0) {
+            let ratio = resources.open_fds as f64 / limit as f64;
+            if ratio >= USAGE_ALERT {
+                alerts.push((
+                    Severity::Critical,
+                    format!("{} open files, {:.0}% of the limit ({})", resources.open_fds, ratio * 100., limit),
+                ));
+            }
+        }
+        if let Some(limit) = resources.max_map_count.filter(|limit| *limit > 0) {
+            let ratio = resources.mmaps as f64 / limit as f64;
+            if ratio >= USAGE_ALERT {
+                alerts.push((
+                    Severity::Critical,
+                    format!("{} memory maps, {:.0}% of vm.max_map_count ({})", resources.mmaps, ratio * 100., limit),
+                ));
+            }
+        }
+    }
+    if let Some(Ok(comparison)) = &app.data.reference {
+        if comparison.diverged() {
+            alerts.push((
+                Severity::Critical,
+                format!("Block {} differs from the one of the reference node", comparison.height),
+            ));
+        } else if comparison.lag_blocks() > REFERENCE_LAG_ALERT {
+            alerts.push((
+                Severity::Warning,
+                format!(
+                    "{} blocks ({}s) behind the reference node",
+                    comparison.lag_blocks(),
+                    comparison.lag_seconds()
+                ),
+            ));
+        }
+    }
+    if app.data.disk_size > 0 && (app.data.available_storage as f64) < (1. - USAGE_ALERT) * app.data.disk_size as f64 {
+        alerts.push((Severity::Warning, "Less than 20% of the disk is available".to_string()));
+    }
+    alerts.sort_by_key(|(severity, _)| std::cmp::Reverse(*severity));
+    alerts
+}
+
+pub fn render_alerts(frame: &mut Frame, app: &App, area: Rect) {
+    render_zone(frame, area, "Alerts");
+    let theme = app.theme();
+    let alerts = active_alerts(app);
+    let lines: Vec<Line> = if alerts.is_empty() {
+        vec![Line::raw("No active alert").style(theme.text)]
+    } else {
+        alerts
+            .into_iter()
+            .map(|(severity, message)| match severity {
+                Severity::Critical => Line::raw(format!("CRITICAL  {}", message)).fg(theme.critical).bold(),
+                Severity::Warning => Line::raw(format!("WARNING   {}", message)).fg(theme.warning),
+            })
+            .collect()
+    };
+    frame.render_widget(Paragraph::new(lines), area.inner(&Margin::new(1, 1)));
+}
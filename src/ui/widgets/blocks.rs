@@ -0,0 +1,101 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Row, Table, TableState};
+use starknet::core::types::BlockStatus;
+
+use super::utils::{format_clock, format_gas_price, short_hex};
+use super::Detail;
+use crate::app::App;
+use crate::keymap::Action;
+
+/// How long a new block stays highlighted in the feed.
+const NEW_BLOCK_HIGHLIGHT: Duration = Duration::from_secs(3);
+
+pub fn blocks_title(app: &App) -> String {
+    let mut title = match app.data.blocks.back() {
+        Some(latest) => format!("Latest blocks (head {})", latest.number),
+        None => "Latest blocks".to_string(),
+    };
+    match app.data.reorgs.len() {
+        0 => {}
+        1 => title.push_str("  1 reorg"),
+        count => title.push_str(&format!("  {} reorgs", count)),
+    }
+    if let Some(key) = app.keymap.key_for(Action::Inspect) {
+        title.push_str(&format!("  ({}: inspect)", key));
+    }
+    title
+}
+
+/// Latest blocks seen by the node, newest first. When zoomed, the sequencer is shown and a block
+/// can be selected to open it in the inspector.
+pub fn render_blocks(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    if app.data.blocks.is_empty() {
+        let message = match &app.data.block_number {
+            Err(err) => err.clone(),
+            Ok(_) => "Waiting for the first block".to_string(),
+        };
+        frame.render_widget(Paragraph::new(Line::raw(message).fg(theme.muted)), area);
+        return;
+    }
+
+    let mut header = vec!["Block", "Hash", "Time", "Txs"];
+    let mut widths = vec![Constraint::Length(8), Constraint::Length(15), Constraint::Length(12), Constraint::Length(5)];
+    if detail == Detail::Full {
+        header.push("Sequencer");
+        widths.push(Constraint::Length(15));
+    }
+    header.extend(["L1 gas", "Status", "Interval"]);
+    widths.extend([Constraint::Length(14), Constraint::Length(14), Constraint::Length(8)]);
+
+    let rows: Vec<Row> = app
+        .data
+        .blocks
+        .iter()
+        .rev()
+        .map(|block| {
+            let mut cells = vec![
+                block.number.to_string(),
+                short_hex(&block.hash),
+                format_clock(UNIX_EPOCH + Duration::from_secs(block.timestamp)),
+                block.transaction_count.to_string(),
+            ];
+            if detail == Detail::Full {
+                cells.push(short_hex(&block.sequencer));
+            }
+            cells.extend([
+                format_gas_price(&block.l1_gas_price.price_in_wei),
+                status_name(block.status).to_string(),
+                block.block_time.map_or(String::new(), |seconds| format!("{}s", seconds)),
+            ]);
+            let style = if block.received_at.elapsed() < NEW_BLOCK_HIGHLIGHT {
+                Style::new().fg(theme.emphasis).bold()
+            } else if block.status == BlockStatus::AcceptedOnL1 {
+                Style::new().fg(theme.text)
+            } else {
+                Style::new().fg(theme.muted)
+            };
+            Row::new(cells).style(style)
+        })
+        .collect();
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::new().fg(theme.title).bold()))
+        .column_spacing(1)
+        .highlight_style(Style::new().reversed());
+    let selected = if detail == Detail::Full { Some(app.block_selection) } else { None };
+    frame.render_stateful_widget(table, area, &mut TableState::default().with_selected(selected));
+}
+
+pub fn status_name(status: BlockStatus) -> &'static str {
+    match status {
+        BlockStatus::Pending => "PENDING",
+        BlockStatus::AcceptedOnL2 => "ACCEPTED_ON_L2",
+        BlockStatus::AcceptedOnL1 => "ACCEPTED_ON_L1",
+        BlockStatus::Rejected => "REJECTED",
+    }
+}
@@ -0,0 +1,199 @@
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Axis, Chart, Dataset, GraphType};
+
+use super::utils::{
+    cursor_dataset, cursor_line, ema_serie, format_clock, inspected_sample, raw_serie, render_tooltip, smooth_serie,
+};
+use super::Detail;
+use crate::app::App;
+use crate::config::{ChartMarker, ChartOptions, Smoothing, YBounds, YScale};
+
+/// One measured series of a chart.
+pub struct Serie<'a> {
+    pub label: &'a str,
+    pub values: &'a [f64],
+    pub color: Color,
+}
+
+/// What a chart shows: its series, the highest value they can take when there is one, how to
+/// write a value in their unit, and whether they hold one sample per frame. Only those line up
+/// with the time cursor.
+pub struct ChartData<'a> {
+    pub series: Vec<Serie<'a>>,
+    pub capacity: Option<f64>,
+    pub format: &'a dyn Fn(f64) -> String,
+    pub per_frame: bool,
+}
+
+impl Smoothing {
+    pub fn apply(self, values: &[f64]) -> Vec<(f64, f64)> {
+        match self {
+            Smoothing::Raw => raw_serie(values),
+            Smoothing::MovingAverage(window) => smooth_serie(values, window.max(1)),
+            Smoothing::Exponential(alpha) => ema_serie(values, alpha.clamp(0., 1.)),
+        }
+    }
+    pub fn next(self) -> Self {
+        match self {
+            Smoothing::Raw => Smoothing::MovingAverage(5),
+            Smoothing::MovingAverage(_) => Smoothing::Exponential(0.3),
+            Smoothing::Exponential(_) => Smoothing::Raw,
+        }
+    }
+    fn describe(self) -> String {
+        match self {
+            Smoothing::Raw => "raw".to_string(),
+            Smoothing::MovingAverage(window) => format!("avg {}", window),
+            Smoothing::Exponential(alpha) => format!("ema {}", alpha),
+        }
+    }
+}
+
+impl ChartMarker {
+    pub fn next(self) -> Self {
+        match self {
+            ChartMarker::Braille => ChartMarker::Dot,
+            ChartMarker::Dot => ChartMarker::Block,
+            ChartMarker::Block => ChartMarker::Bar,
+            ChartMarker::Bar => ChartMarker::Braille,
+        }
+    }
+    fn symbol(self) -> Marker {
+        match self {
+            ChartMarker::Braille => Marker::Braille,
+            ChartMarker::Dot => Marker::Dot,
+            ChartMarker::Block => Marker::Block,
+            ChartMarker::Bar => Marker::Bar,
+        }
+    }
+}
+
+impl YScale {
+    pub fn next(self) -> Self {
+        match self {
+            YScale::Linear => YScale::Log,
+            YScale::Log => YScale::Linear,
+        }
+    }
+    /// Position of `value` on the axis. The log scale is shifted by one so that zero stays at zero.
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            YScale::Linear => value,
+            YScale::Log => (1. + value.max(0.)).log10(),
+        }
+    }
+    fn invert(self, position: f64) -> f64 {
+        match self {
+            YScale::Linear => position,
+            YScale::Log => 10f64.powf(position) - 1.,
+        }
+    }
+}
+
+impl ChartOptions {
+    /// Short reminder of the options, shown under the chart.
+    fn describe(&self) -> String {
+        let mut parts = vec![self.smoothing.describe()];
+        if self.scale == YScale::Log {
+            parts.push("log".to_string());
+        }
+        if self.bounds == YBounds::Auto {
+            parts.push("auto".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// Draws the series as configured by `options`, along with the time cursor and the values under
+/// the pointer.
+pub fn render_chart(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    options: &ChartOptions,
+    data: ChartData,
+    detail: Detail,
+) {
+    let ChartData { series, capacity, format, per_frame } = data;
+    let cursor = app.cursor.filter(|_| per_frame);
+    let theme = app.theme();
+    let len = series.iter().map(|serie| serie.values.len()).max().unwrap_or(0);
+    let x_max = len.saturating_sub(1).max(1) as f64;
+
+    let highest = series.iter().flat_map(|serie| serie.values.iter()).fold(0., |acc: f64, value| acc.max(*value));
+    let (lower, upper) = match (options.bounds, capacity) {
+        (YBounds::Fixed(lower, upper), _) => (lower, upper),
+        (YBounds::Capacity, Some(capacity)) => (0., capacity),
+        _ => (0., highest),
+    };
+    let upper = if upper > lower { upper } else { lower + 1. };
+    let (bottom, top) = (options.scale.apply(lower), options.scale.apply(upper));
+    let scaled = |points: Vec<(f64, f64)>| -> Vec<(f64, f64)> {
+        points.into_iter().map(|(x, y)| (x, options.scale.apply(y))).collect()
+    };
+
+    let smoothed: Vec<Vec<(f64, f64)>> =
+        series.iter().map(|serie| scaled(options.smoothing.apply(serie.values))).collect();
+    // Samples are shown with the smoothed lines when zoomed, unless these are the samples already
+    let measured: Vec<Vec<(f64, f64)>> = match (detail, options.smoothing) {
+        (Detail::Full, Smoothing::MovingAverage(_) | Smoothing::Exponential(_)) => {
+            series.iter().map(|serie| scaled(raw_serie(serie.values))).collect()
+        }
+        _ => vec![],
+    };
+    let graph_type = if options.smoothing == Smoothing::Raw { GraphType::Scatter } else { GraphType::Line };
+    let mut datasets: Vec<Dataset> = series
+        .iter()
+        .zip(smoothed.iter())
+        .map(|(serie, points)| {
+            let current = serie.values.last().map_or(String::new(), |value| format(*value));
+            Dataset::default()
+                .name(format!("{} {}", serie.label, current))
+                .marker(options.marker.symbol())
+                .graph_type(graph_type)
+                .style(Style::default().fg(serie.color))
+                .data(points)
+        })
+        .collect();
+    for points in measured.iter() {
+        datasets.push(Dataset::default().marker(Marker::Dot).style(Style::default().fg(theme.measured)).data(points));
+    }
+    let cursor_points = cursor_line(cursor, bottom, top);
+    if !cursor_points.is_empty() {
+        datasets.push(cursor_dataset(&cursor_points, theme));
+    }
+
+    let label_count = if detail == Detail::Full { 5 } else { 3 };
+    let labels: Vec<Span> = (0..label_count)
+        .map(|i| bottom + (top - bottom) * i as f64 / (label_count - 1) as f64)
+        .map(|position| format(options.scale.invert(position)).bold())
+        .collect();
+    let label_width = labels.iter().map(Span::width).max().unwrap_or(0) as u16;
+    let chart = Chart::new(datasets)
+        .x_axis(
+            Axis::default()
+                .title(options.describe())
+                .style(Style::default().fg(theme.muted))
+                .labels(vec![])
+                .bounds([0., x_max]),
+        )
+        .y_axis(Axis::default().style(Style::default().fg(theme.muted)).labels(labels).bounds([bottom, top]));
+    frame.render_widget(chart, area);
+
+    if let Some((i, position)) = inspected_sample(app.pointer, cursor, area, label_width, x_max, len) {
+        let mut lines = vec![];
+        if let Some(timestamp) = app.data.timestamps.get(i).filter(|_| per_frame) {
+            lines.push(Line::raw(format_clock(*timestamp)).style(theme.muted));
+        }
+        for serie in series.iter() {
+            if let Some(value) = serie.values.get(i) {
+                lines.push(Line::raw(format!("{} {}", serie.label, format(*value))).style(serie.color));
+            }
+        }
+        render_tooltip(frame, area, position, lines, theme);
+    }
+}
@@ -3,42 +3,52 @@ use ratatui::prelude::Frame;
 use ratatui::prelude::Margin;
 use ratatui::prelude::Direction;
 use ratatui::prelude::Constraint;
-use ratatui::style::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Chart, Dataset};
 
-use super::utils::{continuous, render_gauge, smooth_serie, render_zone};
+use super::chart::{render_chart, ChartData, Serie};
+use super::utils::{render_gauge, render_zone, Stats};
+use super::Detail;
 use crate::app::App;
 
-pub fn render_cpu(frame: &mut Frame, app: &App, area: Rect) {
+pub fn cpu_title(app: &App) -> String {
+    let health = app.data.cpu_sampler;
+    match health.last_sample_age {
+        None => "CPU (waiting for samples)".to_string(),
+        Some(age) if health.is_stale() => format!("CPU (stale, last sample {}s ago)", age.as_secs()),
+        Some(_) => format!("CPU ({})", app.data.cpu_name),
+    }
+}
+
+/// Usage of the target now and over the visible window.
+pub fn cpu_readout(app: &App, detail: Detail) -> String {
+    match Stats::of(&app.data.cpu_usage) {
+        Some(stats) => stats.readout(&format!("CPU {:.0}%", stats.current), detail, |usage| format!("{:.0}%", usage)),
+        None => "CPU".to_string(),
+    }
+}
+
+pub fn render_cpu(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
     let layout = Layout::default()
     .direction(Direction::Vertical)
     .constraints(vec![Constraint::Percentage(80), Constraint::Percentage(20)])
     .flex(Flex::Center)
     .margin(0)
     .split(area);
-    render_zone(frame, layout[1], "Used");
+    render_zone(frame, layout[1], &cpu_readout(app, detail));
     render_cpu_gauge(frame, app, layout[1].inner(&Margin::new(1, 1)));
-    render_cpu_graph(frame, app, layout[0]);
+    render_cpu_graph(frame, app, layout[0], detail);
 }
 
-fn render_cpu_graph(frame: &mut Frame, app: &App, area: Rect) {
-    let serie = continuous(smooth_serie(&app.data.cpu_usage, 5));
-    let datasets = vec![
-        Dataset::default().name("CPU").marker(Marker::Braille).style(Style::default().fg(Color::Cyan)).data(&serie),
-    ];
-    let chart = Chart::new(datasets)
-        .x_axis(Axis::default().title("t").style(Style::default().fg(Color::Gray)).labels(vec![]).bounds([0., 100.]))
-        .y_axis(
-            Axis::default()
-                .style(Style::default().fg(Color::Gray))
-                .labels(vec!["0%".bold(), "50%".bold(), "100%".bold()])
-                .bounds([0., 101.]),
-        );
-    frame.render_widget(chart, area);
+fn render_cpu_graph(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let data = ChartData {
+        series: vec![Serie { label: "CPU", values: &app.data.cpu_usage, color: app.theme().cpu }],
+        capacity: Some(100.),
+        format: &|usage| format!("{:.0}%", usage),
+        per_frame: true,
+    };
+    render_chart(frame, app, area, &app.charts.cpu, data, detail);
 }
 
 fn render_cpu_gauge(frame: &mut Frame, app: &App, area: Rect) {
-    let serie = smooth_serie(&app.data.cpu_usage, 20);
-    render_gauge(frame, area, serie.last().unwrap().1 / 100., true)
+    let serie = app.charts.cpu.smoothing.apply(&app.data.cpu_usage);
+    render_gauge(frame, area, serie.last().map_or(0., |(_, usage)| usage / 100.), true, app.theme())
 }
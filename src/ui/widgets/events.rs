@@ -0,0 +1,89 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Row, Table};
+use starknet::core::types::FieldElement;
+
+use super::utils::{decode_felt, short_hex};
+use super::Detail;
+use crate::app::App;
+use crate::keymap::Action;
+
+pub fn events_title(app: &App) -> String {
+    match &app.event_subscription {
+        Some(subscription) => format!(
+            "Events of {} ({} keys filtered, {} received)",
+            short_hex(&subscription.address),
+            subscription.keys.iter().filter(|values| !values.is_empty()).count(),
+            app.data.events.len()
+        ),
+        None => "Events".to_string(),
+    }
+}
+
+/// Live stream of the events of the subscription, newest first. Zoomed, the hashes are written in
+/// full along with the keys.
+pub fn render_events(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    if app.event_subscription.is_none() {
+        let hint = match app.keymap.key_for(Action::Command) {
+            Some(key) => format!("Type {}events <contract address> [key ...] to follow the events of a contract", key),
+            None => "No contract followed".to_string(),
+        };
+        let lines = vec![
+            Line::raw(hint).fg(theme.text),
+            Line::raw("Each key lists its accepted values separated by |, or is _ to accept any").fg(theme.muted),
+        ];
+        frame.render_widget(Paragraph::new(lines), area);
+        return;
+    }
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    let status = match &app.data.events_error {
+        Some(err) => Line::raw(err.clone()).fg(theme.warning),
+        None if app.data.events.is_empty() => Line::raw("Waiting for matching events from the head on").fg(theme.muted),
+        None => Line::raw(format!("{} latest events", app.data.events.len())).fg(theme.muted),
+    };
+    frame.render_widget(Paragraph::new(status), zones[0]);
+
+    let (felt, hash_width): (fn(&FieldElement) -> String, _) = match detail {
+        Detail::Full => (|value| format!("{:#x}", value), Constraint::Length(66)),
+        Detail::Compact => (short_hex, Constraint::Length(15)),
+    };
+    let felts = |values: &[FieldElement], format: fn(&FieldElement) -> String| {
+        values.iter().map(format).collect::<Vec<_>>().join(" ")
+    };
+    let mut header = vec!["Block", "Transaction"];
+    let mut widths = vec![Constraint::Length(8), hash_width];
+    if detail == Detail::Full {
+        header.push("Keys");
+        widths.push(Constraint::Percentage(30));
+    }
+    header.push("Data");
+    widths.push(Constraint::Min(0));
+    let rows: Vec<Row> = app
+        .data
+        .events
+        .iter()
+        .rev()
+        .map(|event| {
+            let mut cells = vec![
+                event.block_number.map_or("pending".to_string(), |number| number.to_string()),
+                felt(&event.transaction_hash),
+            ];
+            if detail == Detail::Full {
+                cells.push(felts(&event.keys, short_hex));
+            }
+            cells.push(felts(&event.data, decode_felt));
+            Row::new(cells)
+        })
+        .collect();
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::new().fg(theme.title).bold()))
+        .column_spacing(1)
+        .style(Style::new().fg(theme.text));
+    frame.render_widget(table, zones[1]);
+}
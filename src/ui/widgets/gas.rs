@@ -0,0 +1,65 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use starknet::core::types::PriceUnit;
+
+use super::chart::{render_chart, ChartData, Serie};
+use super::utils::{format_price, Stats};
+use super::Detail;
+use crate::app::App;
+use crate::keymap::Action;
+
+pub fn gas_price_title(app: &App) -> String {
+    let (current, other) = match app.gas_unit {
+        PriceUnit::Wei => ("ETH", "STRK"),
+        PriceUnit::Fri => ("STRK", "ETH"),
+    };
+    match app.keymap.key_for(Action::ToggleGasUnit) {
+        Some(key) => format!("L1 gas price in {} ({}: {})", current, key, other),
+        None => format!("L1 gas price in {}", current),
+    }
+}
+
+/// Price of L1 gas in each block of the feed, under its extremes.
+pub fn render_gas_price(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    let unit = app.gas_unit;
+    let theme = app.theme();
+    let price = |price: f64| format_price(price, unit);
+
+    let prices: Vec<f64> = app
+        .data
+        .blocks
+        .iter()
+        .map(|block| match unit {
+            PriceUnit::Wei => block.l1_gas_price.price_in_wei,
+            PriceUnit::Fri => block.l1_gas_price.price_in_fri,
+        })
+        .filter_map(|price| u128::try_from(price).ok())
+        .map(|price| price as f64)
+        .collect();
+    let readout = match Stats::of(&prices) {
+        Some(stats) => Line::raw(format!(
+            "Last {} blocks: min {}, avg {}, max {}",
+            prices.len(),
+            price(stats.min),
+            price(stats.avg),
+            price(stats.max)
+        )),
+        None => Line::raw("Waiting for the first block").fg(theme.muted),
+    };
+    frame.render_widget(Paragraph::new(readout).fg(theme.text), zones[0]);
+
+    let data = ChartData {
+        series: vec![Serie { label: "L1 gas", values: &prices, color: theme.tx }],
+        capacity: None,
+        format: &price,
+        per_frame: false,
+    };
+    render_chart(frame, app, zones[1], &app.charts.gas_price, data, detail);
+}
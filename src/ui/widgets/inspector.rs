@@ -0,0 +1,302 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Row, Table, TableState, Wrap};
+use starknet::core::types::{
+    BlockId, BlockTag, ExecutionResult, FieldElement, TransactionExecutionStatus, TransactionFinalityStatus,
+    TransactionStatus,
+};
+
+use super::blocks::status_name;
+use super::utils::{format_clock, format_fee, format_gas_price, render_zone, short_hex};
+use crate::app::{App, Inspector};
+use crate::explorer::{Answer, BlockDetail, MessageStatus, Query, TransactionDetail};
+use crate::ui::theme::Theme;
+
+/// Answer to the last query, over the whole page for a block, in `area` for a transaction.
+pub fn render_inspector(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(inspector) = &app.inspector else {
+        return;
+    };
+    let theme = app.theme();
+    match &inspector.answer {
+        // Receipts are handed to their transaction, never kept as the answer
+        None | Some(Ok(Answer::Receipt(_))) => {
+            render_zone(frame, area, &query_title(&inspector.query));
+            let waiting = Line::raw("Waiting for the node…").fg(theme.muted);
+            frame.render_widget(Paragraph::new(waiting), area.inner(&Margin::new(1, 1)));
+        }
+        Some(Err(err)) => {
+            render_zone(frame, area, &query_title(&inspector.query));
+            let error = Paragraph::new(err.as_str()).wrap(Wrap { trim: true }).fg(theme.warning);
+            frame.render_widget(error, area.inner(&Margin::new(1, 1)));
+        }
+        Some(Ok(Answer::Block(block))) => match block.transactions.get(inspector.selected) {
+            Some(transaction) if inspector.expanded => {
+                render_zone(frame, area, &format!("Transaction {} of block {}", inspector.selected, block.number));
+                let lines = transaction_lines(theme, transaction);
+                render_scrolled(frame, lines, inspector.scroll, area.inner(&Margin::new(1, 1)));
+            }
+            _ => {
+                render_zone(frame, area, &format!("Block {}", block.number));
+                render_block(frame, theme, block, inspector, area.inner(&Margin::new(1, 1)));
+            }
+        },
+        Some(Ok(Answer::Transaction { transaction, status })) => {
+            render_zone(frame, area, &format!("Transaction {}", short_hex(&transaction.hash)));
+            let status = match status {
+                Ok(status) => Line::raw(format!("Status      {}", status_text(status))).fg(theme.emphasis).bold(),
+                Err(err) => Line::raw(format!("Status      unknown, {}", err)).fg(theme.warning),
+            };
+            let mut lines = vec![status];
+            lines.extend(transaction_lines(theme, transaction));
+            render_scrolled(frame, lines, inspector.scroll, area.inner(&Margin::new(1, 1)));
+        }
+        Some(Ok(Answer::Messages(messages))) => {
+            render_zone(frame, area, &query_title(&inspector.query));
+            let lines = match messages.is_empty() {
+                true => vec![Line::raw("The node knows no message sent by this transaction").fg(theme.muted)],
+                false => message_lines(theme, messages),
+            };
+            render_scrolled(frame, lines, inspector.scroll, area.inner(&Margin::new(1, 1)));
+        }
+    }
+}
+
+/// One paragraph per message, in the order they were sent.
+fn message_lines<'a>(theme: &Theme, messages: &'a [MessageStatus]) -> Vec<Line<'a>> {
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", name), Style::new().fg(theme.muted)),
+            Span::styled(value, Style::new().fg(theme.text)),
+        ])
+    };
+    let mut lines = Vec::new();
+    for (i, message) in messages.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::raw(format!("Message {}", i)).fg(theme.title).bold());
+        lines.push(field("L2 tx", message.transaction_hash.clone()));
+        lines.push(field("Finality", message.finality_status.clone()));
+        if let Some(reason) = &message.failure_reason {
+            lines.push(field("Failure", reason.clone()).fg(theme.warning));
+        }
+    }
+    lines
+}
+
+fn status_text(status: &TransactionStatus) -> String {
+    let execution = |status: &TransactionExecutionStatus| match status {
+        TransactionExecutionStatus::Succeeded => "SUCCEEDED",
+        TransactionExecutionStatus::Reverted => "REVERTED",
+    };
+    match status {
+        TransactionStatus::Received => "RECEIVED".to_string(),
+        TransactionStatus::Rejected => "REJECTED".to_string(),
+        TransactionStatus::AcceptedOnL2(status) => format!("ACCEPTED_ON_L2, {}", execution(status)),
+        TransactionStatus::AcceptedOnL1(status) => format!("ACCEPTED_ON_L1, {}", execution(status)),
+    }
+}
+
+fn query_title(query: &Query) -> String {
+    match query {
+        Query::Block(BlockId::Number(number)) => format!("Block {}", number),
+        Query::Block(BlockId::Hash(hash)) => format!("Block {}", short_hex(hash)),
+        Query::Block(BlockId::Tag(BlockTag::Latest)) => "Latest block".to_string(),
+        Query::Block(BlockId::Tag(BlockTag::Pending)) => "Pending block".to_string(),
+        Query::Transaction(hash) => format!("Transaction {}", short_hex(hash)),
+        Query::Messages(hash) => format!("Messages from L1 transaction {}", hash),
+        Query::Receipt(hash) => format!("Receipt of {}", short_hex(hash)),
+    }
+}
+
+/// Header of the block over its transactions, one of which is selected.
+fn render_block(frame: &mut Frame, theme: &Theme, block: &BlockDetail, inspector: &Inspector, area: Rect) {
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<11}", name), Style::new().fg(theme.muted)),
+            Span::styled(value, Style::new().fg(theme.text)),
+        ])
+    };
+    let header = vec![
+        field("Hash", format!("{:#x}", block.hash)),
+        field("Parent", format!("{:#x}", block.parent_hash)),
+        field("State root", format!("{:#x}", block.new_root)),
+        field("Sequencer", format!("{:#x}", block.sequencer)),
+        field(
+            "Time",
+            format!(
+                "{}  status {}  version {}  L1 gas {}",
+                format_clock(UNIX_EPOCH + Duration::from_secs(block.timestamp)),
+                status_name(block.status),
+                block.starknet_version,
+                format_gas_price(&block.l1_gas_price)
+            ),
+        ),
+        field("Txs", block.transactions.len().to_string()),
+    ];
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(header.len() as u16 + 1), Constraint::Min(0)])
+        .split(area);
+    frame.render_widget(Paragraph::new(header), zones[0]);
+
+    let rows: Vec<Row> = block
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(i, transaction)| {
+            Row::new(vec![
+                i.to_string(),
+                short_hex(&transaction.hash),
+                transaction.kind.name().to_string(),
+                transaction.sender.as_ref().map_or(String::new(), short_hex),
+                transaction.nonce.map_or(String::new(), |nonce| format!("{}", nonce)),
+                transaction.max_fee.as_ref().map_or(String::new(), format_fee),
+                execution_status(transaction).to_string(),
+            ])
+            .style(match &transaction.receipt {
+                Some(Ok(receipt)) if matches!(receipt.execution, ExecutionResult::Reverted { .. }) => {
+                    Style::new().fg(theme.warning)
+                }
+                _ => Style::new().fg(theme.text),
+            })
+        })
+        .collect();
+    let widths = vec![
+        Constraint::Length(4),
+        Constraint::Length(15),
+        Constraint::Length(14),
+        Constraint::Length(15),
+        Constraint::Length(8),
+        Constraint::Length(16),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(vec!["#", "Hash", "Type", "Sender", "Nonce", "Max fee", "Status"])
+                .style(Style::new().fg(theme.title).bold()),
+        )
+        .column_spacing(1)
+        .highlight_style(Style::new().reversed());
+    let mut state = TableState::default().with_selected(Some(inspector.selected));
+    frame.render_stateful_widget(table, zones[1], &mut state);
+}
+
+fn execution_status(transaction: &TransactionDetail) -> &'static str {
+    match &transaction.receipt {
+        Some(Ok(receipt)) => match receipt.execution {
+            ExecutionResult::Succeeded => "SUCCEEDED",
+            ExecutionResult::Reverted { .. } => "REVERTED",
+        },
+        Some(Err(_)) => "unknown",
+        None => "",
+    }
+}
+
+/// Lines scrolled by `scroll`, without going past the last one.
+fn render_scrolled(frame: &mut Frame, lines: Vec<Line>, scroll: u16, area: Rect) {
+    let scroll = scroll.min(lines.len().saturating_sub(area.height as usize) as u16);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), area);
+}
+
+/// Everything known about a transaction, one field per line.
+fn transaction_lines<'a>(theme: &Theme, transaction: &'a TransactionDetail) -> Vec<Line<'a>> {
+    let section = |title: String| Line::raw(title).fg(theme.title).bold();
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", name), Style::new().fg(theme.muted)),
+            Span::styled(value, Style::new().fg(theme.text)),
+        ])
+    };
+    let felt = |value: &FieldElement| Line::raw(format!("  {:#x}", value)).fg(theme.text);
+
+    let mut lines = vec![
+        field("Hash", format!("{:#x}", transaction.hash)),
+        field("Type", transaction.kind.name().to_string()),
+    ];
+    if let Some(sender) = &transaction.sender {
+        lines.push(field("Sender", format!("{:#x}", sender)));
+    }
+    if let Some(nonce) = &transaction.nonce {
+        lines.push(field("Nonce", format!("{}", nonce)));
+    }
+    if let Some(max_fee) = &transaction.max_fee {
+        lines.push(field("Max fee", format_fee(max_fee)));
+    }
+
+    lines.push(Line::raw(""));
+    match &transaction.receipt {
+        None => lines.push(Line::raw("Fetching the receipt…").fg(theme.muted)),
+        Some(Err(err)) => lines.push(Line::raw(format!("No receipt: {}", err)).fg(theme.warning)),
+        Some(Ok(receipt)) => {
+            let finality = match receipt.finality {
+                Some(TransactionFinalityStatus::AcceptedOnL1) => "ACCEPTED_ON_L1",
+                Some(TransactionFinalityStatus::AcceptedOnL2) => "ACCEPTED_ON_L2",
+                None => "PENDING",
+            };
+            lines.push(section("Receipt".to_string()));
+            if let Some(number) = receipt.block_number {
+                lines.push(field("Block", number.to_string()));
+            }
+            lines.push(field("Finality", finality.to_string()));
+            match &receipt.execution {
+                ExecutionResult::Succeeded => lines.push(field("Execution", "SUCCEEDED".to_string())),
+                ExecutionResult::Reverted { reason } => {
+                    lines.push(field("Execution", "REVERTED".to_string()).fg(theme.warning));
+                    lines.push(field("Reason", reason.clone()).fg(theme.warning));
+                }
+            }
+            lines.push(field("Fee", format_fee(&receipt.fee)));
+
+            let resources = &receipt.resources;
+            lines.push(Line::raw(""));
+            lines.push(section("Execution resources".to_string()));
+            lines.push(field("Steps", resources.steps.to_string()));
+            let counters = [
+                ("Memory holes", resources.memory_holes),
+                ("Range check", resources.range_check_builtin_applications),
+                ("Pedersen", resources.pedersen_builtin_applications),
+                ("Poseidon", resources.poseidon_builtin_applications),
+                ("EC op", resources.ec_op_builtin_applications),
+                ("ECDSA", resources.ecdsa_builtin_applications),
+                ("Bitwise", resources.bitwise_builtin_applications),
+                ("Keccak", resources.keccak_builtin_applications),
+                ("Segment arena", resources.segment_arena_builtin),
+            ];
+            for (name, count) in counters {
+                if let Some(count) = count.filter(|count| *count > 0) {
+                    lines.push(field(name, count.to_string()));
+                }
+            }
+
+            lines.push(Line::raw(""));
+            lines.push(section(format!("Events ({})", receipt.events.len())));
+            for (i, event) in receipt.events.iter().enumerate() {
+                lines.push(field(&format!("#{}", i), format!("from {:#x}", event.from_address)));
+                lines.push(Line::raw(format!(" keys ({})", event.keys.len())).fg(theme.muted));
+                lines.extend(event.keys.iter().map(felt));
+                lines.push(Line::raw(format!(" data ({})", event.data.len())).fg(theme.muted));
+                lines.extend(event.data.iter().map(felt));
+            }
+
+            lines.push(Line::raw(""));
+            lines.push(section(format!("Messages to L1 ({})", receipt.messages_sent.len())));
+            for message in receipt.messages_sent.iter() {
+                lines.push(field("From", format!("{:#x}", message.from_address)));
+                lines.push(field("To", format!("{:#x}", message.to_address)));
+                lines.push(Line::raw(format!(" payload ({})", message.payload.len())).fg(theme.muted));
+                lines.extend(message.payload.iter().map(felt));
+            }
+        }
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(section(format!("Calldata ({})", transaction.calldata.len())));
+    lines.extend(transaction.calldata.iter().map(felt));
+    lines
+}
@@ -0,0 +1,34 @@
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use super::utils::format_age;
+use crate::app::App;
+
+/// Where the Ethereum side stands, from the statuses of the blocks rather than the L1 logs.
+pub fn render_l1_sync(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let l1_head = match &app.data.l1_head {
+        Ok(l1_head) => *l1_head,
+        Err(err) => {
+            frame.render_widget(Paragraph::new(Line::raw(err.clone()).fg(theme.warning)), area);
+            return;
+        }
+    };
+    let verified = match l1_head {
+        Some(number) => format!("Verified on L1: block {}", number),
+        None => "Verified on L1: no block yet".to_string(),
+    };
+    let gap = match &app.data.block_number {
+        Ok(head) => format!("Gap to L2 head: {} blocks", head.saturating_sub(l1_head.unwrap_or(0))),
+        Err(_) => "Gap to L2 head: unknown".to_string(),
+    };
+    let updated = match app.data.l1_updated_at {
+        Some(at) => format!("Last L1 update: {} ago", format_age(at.elapsed())),
+        None => "Last L1 update: none seen yet".to_string(),
+    };
+    let lines = vec![Line::raw(verified), Line::raw(gap), Line::raw(updated).fg(theme.muted)];
+    frame.render_widget(Paragraph::new(lines).fg(theme.text), area);
+}
@@ -5,34 +5,38 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 
 use super::utils::render_zone;
+use super::Detail;
 use crate::app::App;
+use crate::ui::layout::Panel;
 
-pub fn render_l1_logs(frame: &mut Frame, app: &App, area: Rect) {
-    render_zone(frame, area, "L1 Logs");
-    let raw: Vec<String> = app.data.l1_logs.clone().into_iter().flatten().collect();
-    if !raw.is_empty() {
-        let logs: Vec<Line<'_>> = raw.into_iter().map(Span::from).map(Line::from).collect();
-        let ll = logs.len();
-        if ll > area.height as usize {
-            let l: Vec<Line> = logs.into_iter().skip(ll - area.height as usize).collect();
-            frame.render_widget(Paragraph::new(l), area.inner(&Margin::new(1, 1)));
-        } else {
-            frame.render_widget(Paragraph::new(logs), area.inner(&Margin::new(1, 1)));
-        }
-    }
+pub fn render_l1_logs(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let scroll = app.log_scroll.get(&Panel::L1Logs).copied().unwrap_or(0);
+    render_logs(frame, "L1 Logs", &app.data.l1_logs, scroll, area, detail);
+}
+
+pub fn render_l2_logs(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let scroll = app.log_scroll.get(&Panel::L2Logs).copied().unwrap_or(0);
+    render_logs(frame, "L2 Logs", &app.data.l2_logs, scroll, area, detail);
 }
 
-pub fn render_l2_logs(frame: &mut Frame, app: &App, area: Rect) {
-    render_zone(frame, area, "L2 Logs");
-    let raw: Vec<String> = app.data.l2_logs.clone().into_iter().flatten().collect();
+/// Renders the latest logs, or the ones `scroll` lines above them.
+fn render_logs(frame: &mut Frame, title: &str, logs: &[Option<String>], scroll: usize, area: Rect, detail: Detail) {
+    let raw: Vec<String> = logs.iter().flatten().cloned().collect();
+    let title = match (detail, scroll) {
+        (Detail::Compact, 0) => title.to_string(),
+        (Detail::Full, 0) => format!("{} ({} lines)", title, raw.len()),
+        (_, scroll) => format!("{} ({} newer lines below)", title, scroll),
+    };
+    render_zone(frame, area, &title);
     if !raw.is_empty() {
+        let inner = area.inner(&Margin::new(1, 1));
         let logs: Vec<Line<'_>> = raw.into_iter().map(Span::from).map(Line::from).collect();
-        let ll = logs.len();
-        if ll > area.height as usize {
-            let l: Vec<Line> = logs.into_iter().skip(ll - area.height as usize).collect();
-            frame.render_widget(Paragraph::new(l), area.inner(&Margin::new(1, 1)));
-        } else {
-            frame.render_widget(Paragraph::new(logs), area.inner(&Margin::new(1, 1)));
+        let ll = logs.len().saturating_sub(scroll);
+        let visible: Vec<Line> = logs.into_iter().take(ll).skip(ll.saturating_sub(inner.height as usize)).collect();
+        let paragraph = Paragraph::new(visible);
+        match detail {
+            Detail::Compact => frame.render_widget(paragraph, inner),
+            Detail::Full => frame.render_widget(paragraph.wrap(Wrap { trim: false }), inner),
         }
     }
 }
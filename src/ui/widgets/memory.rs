@@ -1,42 +1,55 @@
+use humansize::{format_size, BINARY};
 use ratatui::layout::{Layout, Rect};
 use ratatui::prelude::Frame;
 use ratatui::prelude::Margin;
 use ratatui::prelude::Direction;
 use ratatui::prelude::Constraint;
-use ratatui::style::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Chart, Dataset};
 
-use super::utils::{continuous, render_gauge, smooth_serie, render_zone};
+use super::chart::{render_chart, ChartData, Serie};
+use super::utils::{render_gauge, render_zone, Stats};
+use super::Detail;
 use crate::app::App;
 
-pub fn render_memory(frame: &mut Frame, app: &App, area: Rect) {
+/// Resident memory of the target now and over the visible window.
+pub fn memory_readout(app: &App, detail: Detail) -> String {
+    let series: Vec<f64> = app.data.memory_usage.iter().map(|bytes| *bytes as f64).collect();
+    let size = |bytes: f64| format_size(bytes as u64, BINARY);
+    match Stats::of(&series) {
+        Some(stats) => {
+            let current = format!("RSS {} / {}", size(stats.current), format_size(app.data.total_memory, BINARY));
+            stats.readout(&current, detail, size)
+        }
+        None => "RSS".to_string(),
+    }
+}
+
+pub fn render_memory(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
     let layout = Layout::default()
     .direction(Direction::Vertical)
     .constraints(vec![Constraint::Percentage(80), Constraint::Percentage(20)])
     .split(area);
-    render_zone(frame, layout[1], "Used");
+    render_zone(frame, layout[1], &memory_readout(app, detail));
     render_memory_gauge(frame, app, layout[1].inner(&Margin::new(1, 1)));
-    render_memory_graph(frame, app, layout[0]);
+    render_memory_graph(frame, app, layout[0], detail);
 }
 
 fn render_memory_gauge(frame: &mut Frame, app: &App, area: Rect) {
-    render_gauge(frame, area, *app.data.memory_usage.last().unwrap() as f64 / app.data.total_memory as f64, true);
+    render_gauge(
+        frame,
+        area,
+        *app.data.memory_usage.last().unwrap() as f64 / app.data.total_memory as f64,
+        true,
+        app.theme(),
+    );
 }
 
-fn render_memory_graph(frame: &mut Frame, app: &App, area: Rect) {
-    let fserie: Vec<f64> = app.data.memory_usage.clone().into_iter().map(|elm| elm as f64 / 1000000.).collect();
-    let serie = continuous(smooth_serie(&fserie, 5));
-    let datasets = vec![
-        Dataset::default().name("RAM").marker(Marker::Braille).style(Style::default().fg(Color::Magenta)).data(&serie),
-    ];
-    let chart = Chart::new(datasets)
-        .x_axis(Axis::default().title("t").style(Style::default().fg(Color::Gray)).labels(vec![]).bounds([0., 100.]))
-        .y_axis(
-            Axis::default()
-                .style(Style::default().fg(Color::Gray))
-                .labels(vec!["0%".bold(), "50%".bold(), "100%".bold()])
-                .bounds([0., app.data.total_memory as f64 / 1000000.]),
-        );
-    frame.render_widget(chart, area);
+fn render_memory_graph(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let usage: Vec<f64> = app.data.memory_usage.iter().map(|bytes| *bytes as f64).collect();
+    let data = ChartData {
+        series: vec![Serie { label: "RAM", values: &usage, color: app.theme().memory }],
+        capacity: Some(app.data.total_memory as f64),
+        format: &|bytes| format_size(bytes as u64, BINARY),
+        per_frame: true,
+    };
+    render_chart(frame, app, area, &app.charts.memory, data, detail);
 }
@@ -0,0 +1,70 @@
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Row, Table};
+use starknet::core::types::FieldElement;
+
+use super::utils::short_hex;
+use super::Detail;
+use crate::app::App;
+use crate::keymap::Action;
+
+pub fn l1_messages_title(app: &App) -> String {
+    let mut title = format!("Messages from L1 ({})", app.data.l1_messages.len());
+    if let Some(key) = app.keymap.key_for(Action::Command) {
+        title.push_str(&format!("  ({} msg <L1 tx hash>: status)", key));
+    }
+    title
+}
+
+/// L1 handler transactions of the latest blocks, newest first, with the full hashes when zoomed.
+pub fn render_l1_messages(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    if app.data.l1_messages.is_empty() {
+        let waiting = Line::raw("No L1 handler transaction in the blocks seen so far").fg(theme.muted);
+        frame.render_widget(Paragraph::new(waiting), area);
+        return;
+    }
+    let (felt, felt_width): (fn(&FieldElement) -> String, _) = match detail {
+        Detail::Full => (|value| format!("{:#x}", value), Constraint::Length(66)),
+        Detail::Compact => (short_hex, Constraint::Length(15)),
+    };
+    let rows: Vec<Row> = app
+        .data
+        .l1_messages
+        .iter()
+        .rev()
+        .map(|message| {
+            Row::new(vec![
+                message.block_number.to_string(),
+                felt(&message.transaction_hash),
+                eth_address(&message.from_address),
+                felt(&message.contract_address),
+                felt(&message.selector),
+                message.nonce.to_string(),
+                message.payload.len().to_string(),
+            ])
+        })
+        .collect();
+    let widths = vec![
+        Constraint::Length(8),
+        felt_width,
+        Constraint::Length(42),
+        felt_width,
+        felt_width,
+        Constraint::Length(8),
+        Constraint::Length(7),
+    ];
+    let header = Row::new(vec!["Block", "L2 tx", "L1 sender", "Contract", "Selector", "Nonce", "Payload"]);
+    let table = Table::new(rows, widths)
+        .header(header.style(Style::new().fg(theme.title).bold()))
+        .column_spacing(1)
+        .style(Style::new().fg(theme.text));
+    frame.render_widget(table, area);
+}
+
+/// Ethereum addresses are 20 bytes, written in full.
+fn eth_address(address: &FieldElement) -> String {
+    format!("0x{:0>40}", format!("{:x}", address))
+}
@@ -1,6 +1,26 @@
+pub mod alerts;
+pub mod blocks;
+pub mod chart;
 pub mod cpu;
+pub mod events;
+pub mod gas;
+pub mod inspector;
+pub mod l1;
 pub mod logs;
 pub mod memory;
+pub mod messages;
 pub mod network;
+pub mod reorgs;
+pub mod resources;
 pub mod storage;
+pub mod summary;
+pub mod syncing;
+pub mod throughput;
 pub mod utils;
+
+/// How much a widget shows: compact when sharing a page, full when zoomed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detail {
+    Compact,
+    Full,
+}
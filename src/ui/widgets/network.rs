@@ -1,39 +1,120 @@
-use ratatui::layout::{Margin, Rect};
+use humansize::{format_size, BINARY, DECIMAL};
+use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect};
 use ratatui::prelude::Frame;
-use ratatui::style::{Color, Style, Stylize};
-use ratatui::symbols::Marker;
-use ratatui::widgets::{Axis, Chart, Dataset};
-
-use super::utils::{continuous, smooth_serie};
-use crate::app::App;
-
-pub fn render_network_graph(frame: &mut Frame, app: &App, area: Rect) {
-    let rx_serie = continuous(smooth_serie(&app.data.rx_flow, 5));
-    let tx_serie = continuous(smooth_serie(&app.data.tx_flow, 5));
-    let rx_max = rx_serie.iter().map(|(_, elm)| elm).max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-    let tx_max = tx_serie.iter().map(|(_, elm)| elm).max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-    let ymax = std::cmp::max_by(rx_max, tx_max, |a, b| a.partial_cmp(b).unwrap());
-    let y_labels = (0..3)
-        .map(|i| 0. + ymax * i as f64 / 2.)
-        .map(|elm| (((elm * 100.).round() / 100.).to_string() + " Mb/s").bold())
-        .collect();
-    let rx_dataset = Dataset::default()
-        .name("Receiving")
-        .marker(Marker::Braille)
-        .style(Style::default().fg(Color::Green))
-        .data(&rx_serie);
-    let tx_dataset = Dataset::default()
-        .name("Sending")
-        .marker(Marker::Braille)
-        .style(Style::default().fg(Color::LightRed))
-        .data(&tx_serie);
-    let chart = Chart::new(vec![tx_dataset, rx_dataset])
-        .x_axis(Axis::default().title("t").style(Style::default().fg(Color::Gray)).labels(vec![]).bounds([0., 100.]))
-        .y_axis(
-            Axis::default()
-                .style(Style::default().fg(Color::Gray))
-                .labels(y_labels)
-                .bounds([0., if *ymax != 0. { *ymax } else { 0.1 }]),
-        );
-    frame.render_widget(chart, area.inner(&Margin::new(1, 1)));
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use super::chart::{render_chart, ChartData, Serie};
+use super::utils::Stats;
+use super::Detail;
+use crate::app::{App, RateUnit};
+use crate::keymap::Action;
+
+const SI_PREFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+const IEC_PREFIXES: [&str; 5] = ["", "Ki", "Mi", "Gi", "Ti"];
+
+/// Picks the divisor and label to express `max` bytes per second in `unit`, so that every value
+/// up to `max` reads as a number between 1 and 1000 (or 1024).
+pub fn rate_scale(max: f64, unit: RateUnit) -> (f64, String) {
+    let (base, prefixes) = if unit.is_iec() { (1024., IEC_PREFIXES) } else { (1000., SI_PREFIXES) };
+    let bits = if unit.is_bits() { 8. } else { 1. };
+    let mut divisor = 1.;
+    let mut exponent = 0;
+    while max * bits / divisor >= base && exponent < prefixes.len() - 1 {
+        divisor *= base;
+        exponent += 1;
+    }
+    let suffix = if unit.is_bits() { "b/s" } else { "B/s" };
+    (divisor / bits, format!("{}{}", prefixes[exponent], suffix))
+}
+
+pub fn format_rate(bytes_per_second: f64, unit: RateUnit) -> String {
+    let (divisor, label) = rate_scale(bytes_per_second, unit);
+    format!("{:.2} {}", bytes_per_second / divisor, label)
+}
+
+pub fn render_network_graph(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Length(1), Constraint::Min(0)])
+        .split(area.inner(&Margin::new(1, 1)));
+    render_network_totals(frame, app, zones[0]);
+    render_network_readout(frame, app, zones[1], detail);
+
+    let unit = app.network_unit;
+    let theme = app.theme();
+    let data = ChartData {
+        series: vec![
+            Serie { label: "Sending", values: &app.data.tx_flow, color: theme.tx },
+            Serie { label: "Receiving", values: &app.data.rx_flow, color: theme.rx },
+        ],
+        capacity: None,
+        format: &|bytes_per_second| format_rate(bytes_per_second, unit),
+        per_frame: true,
+    };
+    render_chart(frame, app, zones[2], &app.charts.network, data, detail);
+}
+
+/// Rates now and over the visible window, in each direction.
+fn render_network_readout(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let unit = app.network_unit;
+    let theme = app.theme();
+    let rate = |bytes_per_second: f64| format_rate(bytes_per_second, unit);
+    let mut spans = vec![];
+    if let Some(stats) = Stats::of(&app.data.rx_flow) {
+        spans.push(Span::raw(stats.readout(&format!("Rx {}", rate(stats.current)), detail, rate)).fg(theme.rx));
+    }
+    if let Some(stats) = Stats::of(&app.data.tx_flow) {
+        spans.push(Span::raw("  "));
+        spans.push(Span::raw(stats.readout(&format!("Tx {}", rate(stats.current)), detail, rate)).fg(theme.tx));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn render_network_totals(frame: &mut Frame, app: &App, area: Rect) {
+    let format = if app.network_unit.is_iec() { BINARY } else { DECIMAL };
+    let mut totals = format!(
+        "Total received: {}  Total sent: {}",
+        format_size(app.data.rx_total, format),
+        format_size(app.data.tx_total, format)
+    );
+    if let Some(key) = app.keymap.key_for(Action::CycleNetworkUnit) {
+        totals.push_str(&format!("  ({}: change unit)", key));
+    }
+    let totals = Line::raw(totals).style(app.theme().muted);
+    frame.render_widget(Paragraph::new(totals), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_scale_keeps_values_below_the_base() {
+        assert_eq!(rate_scale(999., RateUnit::SiBytes), (1., "B/s".to_string()));
+        assert_eq!(rate_scale(1000., RateUnit::SiBytes), (1000., "kB/s".to_string()));
+        assert_eq!(rate_scale(1000., RateUnit::IecBytes), (1., "B/s".to_string()));
+        assert_eq!(rate_scale(3. * 1024. * 1024., RateUnit::IecBytes), (1024. * 1024., "MiB/s".to_string()));
+    }
+
+    #[test]
+    fn rate_scale_counts_bits() {
+        // 125 kB/s is 1 Mb/s
+        assert_eq!(rate_scale(125_000., RateUnit::SiBits), (125_000., "Mb/s".to_string()));
+        assert_eq!(rate_scale(100., RateUnit::IecBits), (1. / 8., "b/s".to_string()));
+    }
+
+    #[test]
+    fn rate_scale_stops_at_the_last_prefix() {
+        let (divisor, label) = rate_scale(1e18, RateUnit::SiBytes);
+        assert_eq!((divisor, label.as_str()), (1e12, "TB/s"));
+    }
+
+    #[test]
+    fn format_rate_uses_its_own_scale() {
+        assert_eq!(format_rate(0., RateUnit::SiBytes), "0.00 B/s");
+        assert_eq!(format_rate(1536., RateUnit::IecBytes), "1.50 KiB/s");
+        assert_eq!(format_rate(125_000., RateUnit::SiBits), "1.00 Mb/s");
+    }
 }
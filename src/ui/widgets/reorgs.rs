@@ -0,0 +1,57 @@
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Row, Table};
+use starknet::core::types::FieldElement;
+
+use super::utils::{format_clock, short_hex};
+use super::Detail;
+use crate::app::App;
+
+pub fn reorgs_title(app: &App) -> String {
+    match app.data.reorgs.len() {
+        0 => "Reorgs".to_string(),
+        count => format!("Reorgs ({})", count),
+    }
+}
+
+/// Reorgs noticed by the chain watcher, newest first, with the full hashes when zoomed.
+pub fn render_reorgs(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    if app.data.reorgs.is_empty() {
+        frame.render_widget(Paragraph::new(Line::raw("No reorg seen").fg(theme.muted)), area);
+        return;
+    }
+    let hash = |hash: &FieldElement| match detail {
+        Detail::Full => format!("{:#x}", hash),
+        Detail::Compact => short_hex(hash),
+    };
+    let hash_width = match detail {
+        Detail::Full => Constraint::Length(66),
+        Detail::Compact => Constraint::Length(15),
+    };
+    let rows: Vec<Row> = app
+        .data
+        .reorgs
+        .iter()
+        .rev()
+        .map(|reorg| {
+            Row::new(vec![
+                format_clock(reorg.detected_at),
+                reorg.number.to_string(),
+                reorg.depth.to_string(),
+                hash(&reorg.old_hash),
+                reorg.new_hash.as_ref().map_or("none".to_string(), hash),
+            ])
+        })
+        .collect();
+    let widths = vec![Constraint::Length(8), Constraint::Length(8), Constraint::Length(5), hash_width, hash_width];
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(vec!["Seen", "From", "Depth", "Old hash", "New hash"]).style(Style::new().fg(theme.title).bold()),
+        )
+        .column_spacing(1)
+        .style(Style::new().fg(theme.warning));
+    frame.render_widget(table, area);
+}
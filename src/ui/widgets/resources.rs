@@ -0,0 +1,81 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::Frame;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use super::utils::render_gauge;
+use super::Detail;
+use crate::app::App;
+use crate::keymap::Action;
+
+fn format_limit(limit: Option<u64>) -> String {
+    limit.map_or("unlimited".to_string(), |limit| limit.to_string())
+}
+
+fn limit_ratio(value: u64, limit: Option<u64>) -> f64 {
+    match limit {
+        Some(limit) if limit > 0 => value as f64 / limit as f64,
+        _ => 0.,
+    }
+}
+
+pub fn render_resources(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    let Some(resources) = &app.data.resources else {
+        frame.render_widget(Paragraph::new("Resource usage unavailable on this platform").style(theme.muted), area);
+        return;
+    };
+    let zones = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let fds = format!(
+        "Open files: {} / {} (hard {})",
+        resources.open_fds,
+        format_limit(resources.fd_soft_limit),
+        format_limit(resources.fd_hard_limit)
+    );
+    frame.render_widget(Paragraph::new(fds).style(theme.text), zones[0]);
+    render_gauge(frame, zones[1], limit_ratio(resources.open_fds, resources.fd_soft_limit), true, theme);
+
+    let mmaps = format!("Memory maps: {} / {}", resources.mmaps, format_limit(resources.max_map_count));
+    frame.render_widget(Paragraph::new(mmaps).style(theme.text), zones[2]);
+    render_gauge(frame, zones[3], limit_ratio(resources.mmaps, resources.max_map_count), true, theme);
+
+    let threads = format!("Threads: {}", resources.threads);
+    frame.render_widget(Paragraph::new(threads).style(theme.text), zones[4]);
+
+    let kinds = match (&resources.fd_kinds, detail) {
+        (Some(kinds), Detail::Compact) => vec![Line::raw(format!(
+            "Files: {}  Sockets: {}  Pipes: {}  Eventfds: {}  Other: {}",
+            kinds.files, kinds.sockets, kinds.pipes, kinds.eventfds, kinds.others
+        ))
+        .style(theme.text)],
+        (Some(kinds), Detail::Full) => [
+            ("Files", kinds.files),
+            ("Sockets", kinds.sockets),
+            ("Pipes", kinds.pipes),
+            ("Eventfds", kinds.eventfds),
+            ("Other", kinds.others),
+        ]
+        .into_iter()
+        .map(|(kind, count)| {
+            let share = if resources.open_fds > 0 { count as f64 / resources.open_fds as f64 * 100. } else { 0. };
+            Line::raw(format!("{:<10}{:>8}  ({:.1}%)", kind, count, share)).style(theme.text)
+        })
+        .collect(),
+        (None, _) => match app.keymap.key_for(Action::ToggleFdKinds) {
+            Some(key) => vec![Line::raw(format!("({}: show file descriptor types)", key)).style(theme.muted)],
+            None => vec![],
+        },
+    };
+    frame.render_widget(Paragraph::new(kinds), zones[5]);
+}
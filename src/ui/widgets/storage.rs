@@ -1,19 +1,39 @@
 use humansize::{format_size, BINARY};
 use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect};
 use ratatui::prelude::Frame;
-use ratatui::style::Color;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
 
 use super::utils::{render_gauge, render_zone};
+use super::Detail;
 use crate::app::App;
 
-pub fn render_storage_data(frame: &mut Frame, app: &App, area: Rect) {
-    let data = vec![
-        Line::raw(format!("Total Disk Space: {}", format_size(app.data.disk_size, BINARY))).style(Color::Green),
-        Line::raw(format!("Node Disk Usage: {}", format_size(app.data.disk_usage, BINARY))).style(Color::Green),
-        Line::raw(format!("Available Space: {}", format_size(app.data.available_storage, BINARY))).style(Color::Green),
+pub fn render_storage_data(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    let mut data = vec![
+        Line::raw(format!("Total Disk Space: {}", format_size(app.data.disk_size, BINARY))).style(theme.text),
+        Line::raw(format!("Node Disk Usage: {}", format_size(app.data.disk_usage, BINARY))).style(theme.text),
+        Line::raw(format!("Available Space: {}", format_size(app.data.available_storage, BINARY))).style(theme.text),
     ];
+    if detail == Detail::Full {
+        data.insert(0, Line::raw(format!("Disk: {}", app.data.disk_name)).style(theme.text));
+    }
+    if detail == Detail::Full && app.data.disk_size > 0 {
+        let disk_size = app.data.disk_size as f64;
+        let used = app.data.disk_size.saturating_sub(app.data.available_storage);
+        data.push(
+            Line::raw(format!(
+                "Disk Used: {} ({:.1}%)",
+                format_size(used, BINARY),
+                used as f64 / disk_size * 100.
+            ))
+            .style(theme.text),
+        );
+        data.push(
+            Line::raw(format!("Node Share of Disk: {:.2}%", app.data.disk_usage as f64 / disk_size * 100.))
+                .style(theme.text),
+        );
+    }
     frame.render_widget(Paragraph::new(data), area);
 }
 
@@ -24,14 +44,14 @@ pub fn render_storage_gauge(frame: &mut Frame, app: &App, area: Rect) {
         app.data.disk_size as f64 / app.data.available_storage as f64
     };
     render_zone(frame, area, "Used");
-    render_gauge(frame, area.inner(&Margin::new(1, 1)), ratio, true);
+    render_gauge(frame, area.inner(&Margin::new(1, 1)), ratio, true, app.theme());
 }
 
-pub fn render_storage(frame: &mut Frame, app: &App, area: Rect) {
+pub fn render_storage(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
     let zones = Layout::default()
         .direction(Direction::Vertical)
         .constraints(vec![Constraint::Ratio(3, 4), Constraint::Ratio(1, 4)])
         .split(area);
     render_storage_gauge(frame, app, zones[1]);
-    render_storage_data(frame, app, zones[0]);
+    render_storage_data(frame, app, zones[0], detail);
 }
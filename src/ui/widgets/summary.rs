@@ -0,0 +1,52 @@
+use humansize::{format_size, BINARY};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use super::alerts::active_alerts;
+use super::cpu::cpu_readout;
+use super::memory::memory_readout;
+use super::network::format_rate;
+use super::Detail;
+use crate::app::App;
+
+/// Key numbers one per line, for terminals too small for the charts.
+pub fn render_summary(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let unit = app.network_unit;
+    let mut lines = vec![
+        Line::raw(cpu_readout(app, Detail::Compact)).fg(theme.cpu),
+        Line::raw(memory_readout(app, Detail::Compact)).fg(theme.memory),
+        Line::raw(format!(
+            "Net Rx {}  Tx {}",
+            format_rate(app.data.rx_flow.last().copied().unwrap_or(0.), unit),
+            format_rate(app.data.tx_flow.last().copied().unwrap_or(0.), unit)
+        ))
+        .fg(theme.text),
+        Line::raw(format!(
+            "Disk {} free of {}, node uses {}",
+            format_size(app.data.available_storage, BINARY),
+            format_size(app.data.disk_size, BINARY),
+            format_size(app.data.disk_usage, BINARY)
+        ))
+        .fg(theme.text),
+    ];
+    if let Some(resources) = &app.data.resources {
+        let limit = resources.fd_soft_limit.map_or("unlimited".to_string(), |limit| limit.to_string());
+        lines.push(
+            Line::raw(format!("Files {} / {}  Threads {}", resources.open_fds, limit, resources.threads))
+                .fg(theme.text),
+        );
+    }
+    lines.push(match &app.data.block_number {
+        Ok(number) => Line::raw(format!("Block {}", number)).fg(theme.text),
+        Err(err) => Line::raw(err.clone()).fg(theme.muted),
+    });
+    lines.push(match active_alerts(app).len() {
+        0 => Line::raw("No alerts").fg(theme.muted),
+        count => Line::raw(format!("{} active alerts", count)).fg(theme.critical).bold(),
+    });
+    frame.render_widget(Paragraph::new(lines), area);
+}
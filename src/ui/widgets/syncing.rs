@@ -1,5 +1,22 @@
-pub fn _render_sync(frame: &mut Frame, app: &App, area: Rect) {
-    let text = match app.data.syncing.clone() {
+use ratatui::layout::{Margin, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use starknet::core::types::SyncStatusType;
+
+use super::utils::short_hex;
+use crate::app::App;
+use crate::chain::Comparison;
+use crate::ui::theme::Theme;
+
+pub fn render_sync(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let block = match &app.data.block_number {
+        Ok(number) => format!("Block: {}", number),
+        Err(err) => err.clone(),
+    };
+    let status = match app.data.syncing.clone() {
         Ok(SyncStatusType::Syncing(status)) => format!(
             "Starting: {} Current: {} Highest: {}",
             status.starting_block_num, status.current_block_num, status.highest_block_num
@@ -7,6 +24,32 @@ pub fn _render_sync(frame: &mut Frame, app: &App, area: Rect) {
         Ok(SyncStatusType::NotSyncing) => "Not Syncing".to_string(),
         Err(err) => err.clone(),
     };
+    let mut lines = vec![Line::raw(block), Line::raw(status)];
+    match &app.data.reference {
+        Some(Ok(comparison)) => lines.push(reference_line(theme, comparison)),
+        Some(Err(err)) => lines.push(Line::raw(format!("Reference: {}", err)).fg(theme.warning)),
+        None => {}
+    }
     frame.render_widget(Block::new().title("Syncing").borders(Borders::ALL), area);
-    frame.render_widget(Paragraph::new(text.as_str()).light_green(), area.inner(&Margin::new(2, 1)));
+    frame.render_widget(Paragraph::new(lines).style(theme.text), area.inner(&Margin::new(2, 1)));
+}
+
+/// How far the node is from the reference, or where they stopped agreeing.
+fn reference_line<'a>(theme: &Theme, comparison: &Comparison) -> Line<'a> {
+    if comparison.diverged() {
+        return Line::raw(format!(
+            "Reference: block {} is {} here but {} there",
+            comparison.height,
+            short_hex(&comparison.local_hash),
+            short_hex(&comparison.reference_hash)
+        ))
+        .fg(theme.critical)
+        .bold();
+    }
+    let lag = match comparison.lag_blocks() {
+        0 => "in sync".to_string(),
+        lag if lag > 0 => format!("{} blocks ({}s) behind", lag, comparison.lag_seconds()),
+        lag => format!("{} blocks ({}s) ahead", -lag, -comparison.lag_seconds()),
+    };
+    Line::raw(format!("Reference: {} {}", comparison.reference_head, lag))
 }
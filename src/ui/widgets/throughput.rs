@@ -0,0 +1,93 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::Frame;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{BarChart, Paragraph};
+
+use super::chart::{render_chart, ChartData, Serie};
+use super::utils::{format_count, Stats};
+use super::Detail;
+use crate::app::App;
+
+/// Upper bounds of the block time buckets, in seconds, the last one catching the rest.
+const BLOCK_TIME_BUCKETS: [(u64, &str); 7] =
+    [(5, "<5s"), (15, "<15s"), (30, "<30s"), (60, "<1m"), (120, "<2m"), (300, "<5m"), (u64::MAX, "5m+")];
+
+pub fn throughput_title(app: &App) -> String {
+    match Stats::of(&app.data.transactions_rate) {
+        Some(stats) => {
+            format!("Chain throughput ({} tx/s, max {})", format_count(stats.current), format_count(stats.max))
+        }
+        None => "Chain throughput".to_string(),
+    }
+}
+
+/// Blocks, transactions, events and Cairo steps per second at each poll of the node, each on its
+/// own scale.
+pub fn render_throughput(frame: &mut Frame, app: &App, area: Rect, detail: Detail) {
+    let theme = app.theme();
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let mut zones = vec![];
+    for row in rows.iter() {
+        zones.extend(
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(*row)
+                .iter()
+                .copied(),
+        );
+    }
+    let charts = [
+        ("Blocks/s", &app.data.blocks_rate, theme.cpu),
+        ("Tx/s", &app.data.transactions_rate, theme.memory),
+        ("Events/s", &app.data.events_rate, theme.rx),
+        ("Steps/s", &app.data.steps_rate, theme.tx),
+    ];
+    for ((label, values, color), zone) in charts.into_iter().zip(zones) {
+        let data = ChartData {
+            series: vec![Serie { label, values, color }],
+            capacity: None,
+            format: &format_count,
+            per_frame: false,
+        };
+        render_chart(frame, app, zone, &app.charts.throughput, data, detail);
+    }
+}
+
+pub fn block_times_title(app: &App) -> String {
+    let times: Vec<f64> = app.data.blocks.iter().filter_map(|block| block.block_time).map(|time| time as f64).collect();
+    match Stats::of(&times) {
+        Some(stats) => format!("Block times (avg {:.0}s, p95 {:.0}s)", stats.avg, stats.p95),
+        None => "Block times".to_string(),
+    }
+}
+
+/// How long the blocks of the feed took to be produced, bucketed.
+pub fn render_block_times(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme();
+    let mut counts = [0u64; BLOCK_TIME_BUCKETS.len()];
+    for time in app.data.blocks.iter().filter_map(|block| block.block_time) {
+        if let Some(bucket) = BLOCK_TIME_BUCKETS.iter().position(|(bound, _)| time < *bound) {
+            counts[bucket] += 1;
+        }
+    }
+    if counts.iter().all(|count| *count == 0) {
+        let waiting = Line::raw("Waiting for consecutive blocks").fg(theme.muted);
+        frame.render_widget(Paragraph::new(waiting), area);
+        return;
+    }
+    let bars: Vec<(&str, u64)> = BLOCK_TIME_BUCKETS.iter().map(|(_, label)| *label).zip(counts).collect();
+    let bar_width = (area.width / BLOCK_TIME_BUCKETS.len() as u16).saturating_sub(1).clamp(1, 6);
+    let chart = BarChart::default()
+        .data(bars.as_slice())
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::new().fg(theme.cpu))
+        .value_style(Style::new().fg(theme.gauge_label).bg(theme.cpu))
+        .label_style(Style::new().fg(theme.muted));
+    frame.render_widget(chart, area);
+}
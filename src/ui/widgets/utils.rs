@@ -1,21 +1,89 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use ratatui::layout::Rect;
 use ratatui::prelude::Frame;
-use ratatui::style::{Color, Stylize};
-use ratatui::widgets::{Block, Borders, Gauge};
-use splines::{Interpolation, Key, Spline};
+use ratatui::style::{Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Dataset, Gauge, GraphType, Paragraph};
+use starknet::core::types::{FieldElement, PriceUnit};
+
+use super::Detail;
+use crate::explorer::Fee;
+use crate::ui::theme::Theme;
 
+/// Centered moving average of `series`, each point placed at the index of the sample it is
+/// centered on. Series shorter than the window are returned as is.
 pub fn smooth_serie(series: &[f64], window_size: usize) -> Vec<(f64, f64)> {
+    let ignore_count = window_size / 2;
+    if series.len() <= 2 * ignore_count {
+        return raw_serie(series);
+    }
     let mut smoothed_series = Vec::new();
+    for i in ignore_count..series.len() - ignore_count {
+        let window = &series[i - ignore_count..=i + ignore_count];
+        smoothed_series.push((i as f64, window.iter().sum::<f64>() / window.len() as f64));
+    }
+    smoothed_series
+}
 
-    let ignore_count = window_size / 2;
+/// Exponential moving average of `series`, each sample weighted by `alpha` against the average
+/// of the previous ones.
+pub fn ema_serie(series: &[f64], alpha: f64) -> Vec<(f64, f64)> {
+    let mut average = series.first().copied().unwrap_or(0.);
+    series
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            average = alpha * value + (1. - alpha) * average;
+            (i as f64, average)
+        })
+        .collect()
+}
 
-    for i in ignore_count..series.len() - ignore_count {
-        let window_average: f64 = series[i - ignore_count..=i + ignore_count].iter().sum::<f64>() / window_size as f64;
-        smoothed_series.push(window_average);
+/// Measured values as chart points, without any smoothing.
+pub fn raw_serie(series: &[f64]) -> Vec<(f64, f64)> {
+    series.iter().enumerate().map(|(i, y)| (i as f64, *y)).collect()
+}
+
+/// Figures summarizing a series over the visible window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub current: f64,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    pub p95: f64,
+}
+
+impl Stats {
+    pub fn of(series: &[f64]) -> Option<Self> {
+        let current = *series.last()?;
+        let mut sorted = series.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Some(Self {
+            current,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            avg: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p95: sorted[((sorted.len() - 1) as f64 * 0.95).round() as usize],
+        })
+    }
+    /// Averages and extremes following the current value, formatted by `format`, for instance
+    /// "37% (avg 22%, max 91%)". Full detail adds the minimum and the 95th percentile.
+    pub fn readout(&self, current: &str, detail: Detail, format: impl Fn(f64) -> String) -> String {
+        match detail {
+            Detail::Compact => format!("{} (avg {}, max {})", current, format(self.avg), format(self.max)),
+            Detail::Full => format!(
+                "{} (min {}, avg {}, p95 {}, max {})",
+                current,
+                format(self.min),
+                format(self.avg),
+                format(self.p95),
+                format(self.max)
+            ),
+        }
     }
-    let x: Vec<f64> = (0..=100).map(|x| x as f64).collect();
-    let serie: Vec<(f64, f64)> = x.into_iter().zip(smoothed_series).collect();
-    serie
 }
 
 pub fn render_zone(frame: &mut Frame, area: Rect, title: &str) {
@@ -23,49 +91,235 @@ pub fn render_zone(frame: &mut Frame, area: Rect, title: &str) {
     frame.render_widget(outline, area);
 }
 
-pub fn render_gauge(frame: &mut Frame, area: Rect, ratio: f64, alert_mode: bool) {
+/// Recolors the border of an already rendered zone, keeping its title.
+pub fn highlight_border(frame: &mut Frame, area: Rect, theme: &Theme) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    let style = Style::new().fg(theme.focus).bold();
+    let buffer = frame.buffer_mut();
+    buffer.set_style(Rect { height: 1, ..area }, style);
+    buffer.set_style(Rect { y: area.bottom() - 1, height: 1, ..area }, style);
+    buffer.set_style(Rect { width: 1, ..area }, style);
+    buffer.set_style(Rect { x: area.right() - 1, width: 1, ..area }, style);
+}
+
+pub fn render_gauge(frame: &mut Frame, area: Rect, ratio: f64, alert_mode: bool, theme: &Theme) {
     let color;
 
     if alert_mode {
         if ratio <= 1. / 3. {
-            color = Color::Green;
+            color = theme.gauge_low;
         } else if ratio <= 2. / 3. {
-            color = Color::Rgb(255, 128, 0)
+            color = theme.gauge_medium
         } else {
-            color = Color::Red;
+            color = theme.gauge_high;
         }
     } else {
-        color = Color::Green
+        color = theme.gauge_low
     }
-    let gauge =
-        Gauge::default().gauge_style(color).fg(Color::Rgb(20, 20, 20)).ratio(if ratio <= 1. { ratio } else { 1. });
+    // Gauge panics on ratios outside 0..=1, which divisions by zero would give
+    let ratio = if ratio.is_finite() { ratio.clamp(0., 1.) } else { 0. };
+    let gauge = Gauge::default().gauge_style(color).fg(theme.gauge_label).ratio(ratio);
     frame.render_widget(gauge, area);
 }
 
-pub fn continuous(points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
-    let keys: Vec<Key<f64, f64>> = points.iter().map(|&(x, y)| Key::new(x, y, Interpolation::Linear)).collect();
+/// Index of the sample under the pointer in a chart rendered in `area`, whose y labels are
+/// `label_width` wide and whose x axis spans `0..=x_max` with one sample per unit.
+fn hovered_sample(
+    pointer: Option<(u16, u16)>,
+    area: Rect,
+    label_width: u16,
+    x_max: f64,
+    len: usize,
+) -> Option<usize> {
+    let (column, row) = pointer?;
+    // The y axis line comes after its labels, the x axis line and its labels take the last two rows
+    let left = area.left() + label_width + 1;
+    let bottom = area.bottom().saturating_sub(2);
+    if len == 0 || column < left || column >= area.right() || row < area.top() || row >= bottom {
+        return None;
+    }
+    let width = (area.right() - left).saturating_sub(1).max(1);
+    let x = (column - left) as f64 / width as f64 * x_max;
+    Some((x.round() as usize).min(len - 1))
+}
 
-    let spline = Spline::from_vec(keys);
+/// Column at which sample `index` is drawn, the inverse of `hovered_sample`.
+fn sample_column(area: Rect, label_width: u16, x_max: f64, index: usize) -> u16 {
+    let left = area.left() + label_width + 1;
+    let width = (area.right().saturating_sub(left)).saturating_sub(1).max(1);
+    left + (index as f64 / x_max * width as f64).round() as u16
+}
 
-    let mut total_distance = 0.0;
-    for window in points.windows(2) {
-        let (x0, y0) = window[0];
-        let (x1, y1) = window[1];
-        total_distance += ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+/// Sample whose values a chart should show, with where to show them: the one under the pointer,
+/// else the one the time cursor is on.
+pub fn inspected_sample(
+    pointer: Option<(u16, u16)>,
+    cursor: Option<usize>,
+    area: Rect,
+    label_width: u16,
+    x_max: f64,
+    len: usize,
+) -> Option<(usize, (u16, u16))> {
+    if let Some(i) = hovered_sample(pointer, area, label_width, x_max, len) {
+        return Some((i, pointer?));
     }
-    let target_distance = 0.01;
-    let points_nb = (total_distance / target_distance).ceil() as usize;
+    let i = cursor?.min(len.checked_sub(1)?);
+    Some((i, (sample_column(area, label_width, x_max, i), area.top())))
+}
 
-    let start = points.first().unwrap().0;
-    let end = points.last().unwrap().0;
-    let ds = (end - start) / (points_nb as f64 - 1.0);
+/// Vertical line at the time cursor from `bottom` to `top`, empty when the cursor is hidden.
+pub fn cursor_line(cursor: Option<usize>, bottom: f64, top: f64) -> Vec<(f64, f64)> {
+    cursor.map_or(vec![], |i| vec![(i as f64, bottom), (i as f64, top)])
+}
+
+pub fn cursor_dataset<'a>(line: &'a [(f64, f64)], theme: &Theme) -> Dataset<'a> {
+    Dataset::default()
+        .graph_type(GraphType::Line)
+        .marker(Marker::Braille)
+        .style(Style::new().fg(theme.focus))
+        .data(line)
+}
 
-    let mut cnt: Vec<(f64, f64)> = Vec::new();
-    for i in 0..points_nb {
-        let x = start + i as f64 * ds;
-        if let Some(y) = spline.sample(x) {
-            cnt.push((x, y));
+/// Draws a small bordered box next to the pointer, kept inside `area`.
+pub fn render_tooltip(frame: &mut Frame, area: Rect, pointer: (u16, u16), lines: Vec<Line>, theme: &Theme) {
+    let width = (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let (column, row) = pointer;
+    let x =
+        if column + 2 + width <= area.right() { column + 2 } else { column.saturating_sub(width + 1).max(area.left()) };
+    let y = row.min(area.bottom().saturating_sub(height)).max(area.top());
+    let tooltip = Rect::new(x, y, width, height);
+    frame.render_widget(Clear, tooltip);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::new().borders(Borders::ALL).border_style(Style::new().fg(theme.focus))),
+        tooltip,
+    );
+}
+
+/// Wall clock time of a sample, in the local time zone where available.
+pub fn format_clock(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    #[cfg(unix)]
+    {
+        let timestamp = seconds as libc::time_t;
+        // SAFETY: localtime_r only writes to the tm it is given, which is plain old data.
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        if !unsafe { libc::localtime_r(&timestamp, &mut tm) }.is_null() {
+            return format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec);
         }
     }
-    cnt
+    format!("{:02}:{:02}:{:02} UTC", seconds / 3600 % 24, seconds / 60 % 60, seconds % 60)
+}
+
+/// Hash or address shortened to its first and last hex digits.
+pub fn short_hex(value: &FieldElement) -> String {
+    let hex = format!("{:#x}", value);
+    if hex.len() <= 14 {
+        return hex;
+    }
+    format!("{}…{}", &hex[..8], &hex[hex.len() - 6..])
+}
+
+/// Gas price in wei, written in gwei.
+pub fn format_gas_price(price: &FieldElement) -> String {
+    match u128::try_from(*price) {
+        Ok(price) => format_price(price as f64, PriceUnit::Wei),
+        Err(_) => format!("{:#x}", price),
+    }
+}
+
+/// Price per unit of gas in gwei, or in gfri for STRK.
+pub fn format_price(price: f64, unit: PriceUnit) -> String {
+    match unit {
+        PriceUnit::Wei => format!("{:.2} gwei", price / 1e9),
+        PriceUnit::Fri => format!("{:.2} gfri", price / 1e9),
+    }
+}
+
+/// Fee in ETH or STRK, both having 18 decimals.
+pub fn format_fee(fee: &Fee) -> String {
+    let token = match fee.unit {
+        PriceUnit::Wei => "ETH",
+        PriceUnit::Fri => "STRK",
+    };
+    format!("{:.6} {}", fee.amount as f64 / 1e18, token)
+}
+
+/// Quantity written with a metric suffix past a thousand, like `1.2M`.
+pub fn format_count(value: f64) -> String {
+    match value.abs() {
+        v if v >= 1e9 => format!("{:.1}G", value / 1e9),
+        v if v >= 1e6 => format!("{:.1}M", value / 1e6),
+        v if v >= 1e3 => format!("{:.1}k", value / 1e3),
+        v if v >= 10. => format!("{:.0}", value),
+        _ => format!("{:.2}", value),
+    }
+}
+
+/// Duration in its two largest units, as in `3m 12s`.
+pub fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds / 60 % 60),
+    }
+}
+
+/// Felts carry no type, so this is a guess: small values read as numbers, printable ones as short
+/// strings, the others in hex.
+pub fn decode_felt(value: &FieldElement) -> String {
+    let number = u64::try_from(*value).ok();
+    if let Some(number) = number.filter(|number| *number <= u32::MAX as u64) {
+        return number.to_string();
+    }
+    let bytes: Vec<u8> = value.to_bytes_be().into_iter().skip_while(|byte| *byte == 0).collect();
+    if bytes.iter().all(|byte| byte.is_ascii_graphic() || *byte == b' ') {
+        return format!("'{}'", String::from_utf8_lossy(&bytes));
+    }
+    match number {
+        Some(number) => number.to_string(),
+        None => short_hex(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_felt_guesses_the_type() {
+        let felt = |hex: &str| FieldElement::from_hex_be(hex).unwrap();
+        assert_eq!(decode_felt(&FieldElement::ZERO), "0");
+        assert_eq!(decode_felt(&felt("0x2a")), "42");
+        // "Transfer" as a short string
+        assert_eq!(decode_felt(&felt("0x5472616e73666572")), "'Transfer'");
+        assert_eq!(decode_felt(&felt("0x100000000")), "4294967296");
+        let hash = felt("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+        assert_eq!(decode_felt(&hash), short_hex(&hash));
+    }
+
+    #[test]
+    fn stats_summarize_the_series() {
+        let series: Vec<f64> = (1..=20).map(f64::from).rev().collect();
+        let stats = Stats::of(&series).unwrap();
+        assert_eq!(stats, Stats { current: 1., min: 1., max: 20., avg: 10.5, p95: 19. });
+    }
+
+    #[test]
+    fn stats_of_a_single_value() {
+        let stats = Stats::of(&[7.]).unwrap();
+        assert_eq!(stats, Stats { current: 7., min: 7., max: 7., avg: 7., p95: 7. });
+        assert_eq!(Stats::of(&[]), None);
+    }
+
+    #[test]
+    fn stats_readout_follows_the_detail() {
+        let stats = Stats::of(&[1., 2., 3., 4.]).unwrap();
+        let format = |value: f64| format!("{:.1}", value);
+        assert_eq!(stats.readout("4", Detail::Compact, format), "4 (avg 2.5, max 4.0)");
+        assert_eq!(stats.readout("4", Detail::Full, format), "4 (min 1.0, avg 2.5, p95 4.0, max 4.0)");
+    }
 }